@@ -0,0 +1,92 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Generation of an archive page listing documents chronologically, grouped by
+//! year and month.
+
+use crate::{escape, library::Document};
+use build_html::{self as html, HtmlContainer};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, collections::BTreeMap};
+
+/// The href of the generated archive page.
+pub const PAGE_HREF: &str = "archive.html";
+
+/// Documents of a single year keyed by month, each given as a tuple of its
+/// date, href, and name.
+type Months<'a> = BTreeMap<u8, Vec<(time::OffsetDateTime, String, &'a str)>>;
+
+/// Which of a [`Document`]'s dates it is archived by.
+///
+/// [`Document`]: Document
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveDate {
+    /// Archive documents by the time they were added to the library.
+    #[default]
+    Created,
+
+    /// Archive documents by the time they were last modified.
+    Modified,
+}
+
+impl ArchiveDate {
+    /// Gets the date of the given [`Document`] that this [`ArchiveDate`]
+    /// refers to.
+    ///
+    /// [`Document`]: Document
+    /// [`ArchiveDate`]: ArchiveDate
+    #[inline]
+    #[must_use]
+    pub fn of(self, doc: &Document) -> time::OffsetDateTime {
+        match self {
+            ArchiveDate::Created => doc.create_time(),
+            ArchiveDate::Modified => doc.mod_time(),
+        }
+    }
+}
+
+/// Creates the content of the archive page from the given tuples of a
+/// [`Document`]'s href and the [`Document`] itself. Years and months are listed
+/// newest first, as are the documents within each month.
+///
+/// [`Document`]: Document
+#[must_use]
+pub fn content<'a>(
+    docs: impl IntoIterator<Item = (String, &'a Document)>,
+    by: ArchiveDate,
+) -> html::Container {
+    let mut years: BTreeMap<i32, Months> = BTreeMap::new();
+
+    for (href, doc) in docs {
+        let date = by.of(doc);
+
+        years
+            .entry(date.year())
+            .or_default()
+            .entry(date.month() as u8)
+            .or_default()
+            .push((date, href, doc.name()));
+    }
+
+    years.into_iter().rev().fold(
+        html::Container::new(html::ContainerType::Div)
+            .with_attributes(vec![("class", "archive")])
+            .with_header(1, "Archive"),
+        |archive, (year, months)| {
+            months
+                .into_iter()
+                .rev()
+                .fold(archive.with_header(2, year), |archive, (_, mut docs)| {
+                    docs.sort_by_key(|(date, _, _)| Reverse(*date));
+
+                    archive
+                        .with_header(3, docs[0].0.month())
+                        .with_container(docs.into_iter().fold(
+                            html::Container::new(html::ContainerType::UnorderedList),
+                            |list, (_, href, name)| list.with_link(href, escape::html(name)),
+                        ))
+                })
+        },
+    )
+}
@@ -89,9 +89,28 @@ where
             }
         };
 
-        for arg in self.args {
+        // Expands grouped single character flags, e.g. "-vv" into "-v -v".
+        let args = self.args.flat_map(|arg| {
             let arg = arg.as_ref();
 
+            match arg.strip_prefix('-') {
+                Some(chars)
+                    if chars.len() > 1
+                        && chars.chars().all(|c| {
+                            self.flags
+                                .iter()
+                                .any(|f| f.single_char() && f.name().starts_with(c))
+                        }) =>
+                {
+                    chars.chars().map(|c| format!("-{}", c)).collect()
+                }
+                _ => vec![arg.to_owned()],
+            }
+        });
+
+        for arg in args {
+            let arg = arg.as_str();
+
             prev = match prev {
                 ArgsItem::Flag(flag @ Flag::Bool(_)) => {
                     match self.commands.iter().find(|c| &*c.0 == arg) {
@@ -169,6 +188,18 @@ impl ParsedArgs {
         map
     }
 
+    /// Gets the number of times the given [`Flag`] was given, e.g. 2 for
+    /// "-v -v" or "-vv".
+    ///
+    /// [`Flag`]: Flag
+    #[must_use]
+    pub fn count(&self, flag: &Flag) -> usize {
+        self.items
+            .iter()
+            .filter(|item| matches!(item, ArgsItem::Flag(f) if f == flag))
+            .count()
+    }
+
     /// Gets a list of all [`Command`]s present in the parsed command line
     /// arguments.
     ///
@@ -200,7 +231,7 @@ impl ParsedArgs {
             ArgsItem::Value(_) => false,
             _ => true,
         }) {
-            Some(pos) => pos,
+            Some(pos) => start_pos + pos,
             None => self.items.len(),
         };
 
@@ -430,4 +461,29 @@ mod tests {
         assert_eq!(commands.len(), 1);
         assert_eq!(commands[0], cmd);
     }
+
+    #[test]
+    fn count_test() {
+        let args = vec!["program", "-vv", "command", "-v", "-xv"];
+        let flag = Flag::Bool("v".into());
+        let cmd = Command("command".into());
+
+        let parsed_args = ArgsParser::new(args.into_iter())
+            .flag(flag.clone())
+            .command(cmd.clone())
+            .parse();
+
+        // "-xv" is not a group of known flags.
+        assert!(parsed_args.is_err());
+
+        let args = vec!["program", "-vv", "command", "-v"];
+        let parsed_args = ArgsParser::new(args.into_iter())
+            .flag(flag.clone())
+            .command(cmd.clone())
+            .parse()
+            .unwrap();
+
+        assert_eq!(parsed_args.count(&flag), 3);
+        assert_eq!(parsed_args.commands(), vec![cmd]);
+    }
 }
@@ -0,0 +1,65 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Authors of documents. A document is credited to the `author` in its front
+//! matter, or else to the site's author, and when a library has several
+//! authors each is given a page listing their documents.
+
+use crate::{escape, href};
+use build_html::{self as html, HtmlContainer};
+
+/// Directory author pages are written to.
+const DIR: &str = "authors";
+
+/// Gets the href of the page of the author of the given name.
+#[must_use]
+pub fn href(name: &str) -> String {
+    format!("{}/{}.html", DIR, href::slugify_name(name))
+}
+
+/// Creates the byline of a document by the author of the given name, linking
+/// to the given href of the author's page if they have one.
+#[must_use]
+pub fn byline(name: &str, page: Option<&str>) -> String {
+    match page {
+        Some(href) => format!(
+            "By <a href=\"{}\" rel=\"author\">{}</a>",
+            href,
+            escape::html(name)
+        ),
+        None => format!("By {}", escape::html(name)),
+    }
+}
+
+/// Creates the content of an author's page from their name and the href and
+/// name of each of their documents.
+#[must_use]
+pub fn content<'a>(
+    name: &str,
+    docs: impl IntoIterator<Item = (String, &'a str)>,
+) -> html::Container {
+    html::Container::new(html::ContainerType::Div)
+        .with_attributes(vec![("class", "author")])
+        .with_header(1, escape::html(name))
+        .with_container(docs.into_iter().fold(
+            html::Container::new(html::ContainerType::UnorderedList),
+            |list, (href, name)| list.with_link(href, escape::html(name)),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byline_test() {
+        assert_eq!(href("Ada Lovelace"), "authors/ada-lovelace.html");
+        assert_eq!(byline("Ada", None), "By Ada");
+        assert_eq!(
+            byline("Ada", Some("../authors/ada.html")),
+            "By <a href=\"../authors/ada.html\" rel=\"author\">Ada</a>"
+        );
+        assert_eq!(byline("<Ada>", None), "By &lt;Ada&gt;");
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Reading of files through a buffer kept between them. Scanning, updating,
+//! and building a library reads every document in turn, so reading each into
+//! the same buffer means a library of thousands of documents is read with a
+//! handful of allocations rather than two for every document. Files are read
+//! rather than memory mapped, since a document may be changed by an editor
+//! while it is being read.
+
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+/// A buffer files are read into one after another, growing to fit the
+/// largest so far and reusing that space for the rest.
+#[derive(Debug, Default)]
+pub struct ReadBuffer {
+    bytes: Vec<u8>,
+}
+
+impl ReadBuffer {
+    /// Creates a new, empty [`ReadBuffer`].
+    ///
+    /// [`ReadBuffer`]: ReadBuffer
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the file at the given path into the buffer, replacing whatever
+    /// it held, and returns its bytes. The file's size is checked first so
+    /// that it is read in as few calls as possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error encountered while opening or reading the file.
+    pub fn read(&mut self, path: impl AsRef<Path>) -> io::Result<&[u8]> {
+        let mut file = fs::File::open(path)?;
+        let len = file.metadata().map_or(0, |m| m.len());
+
+        self.bytes.clear();
+        self.bytes.reserve(usize::try_from(len).unwrap_or_default());
+        file.read_to_end(&mut self.bytes)?;
+
+        Ok(&self.bytes)
+    }
+
+    /// Reads the file at the given path into the buffer like [`read`],
+    /// returning its content as text. The text is borrowed from the buffer
+    /// unless the file is not valid UTF-8, in which case it is copied with
+    /// the invalid bytes replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error encountered while opening or reading the file.
+    ///
+    /// [`read`]: ReadBuffer::read
+    pub fn read_str(&mut self, path: impl AsRef<Path>) -> io::Result<Cow<'_, str>> {
+        Ok(String::from_utf8_lossy(self.read(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_test() {
+        let dir = std::env::temp_dir().join("whim-buffer-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("long.md"), "# Long\n\nA longer document.").unwrap();
+        fs::write(dir.join("short.md"), "# Short").unwrap();
+        fs::write(dir.join("invalid.md"), b"# \xffInvalid").unwrap();
+
+        let mut buffer = ReadBuffer::new();
+        assert_eq!(
+            buffer.read(dir.join("long.md")).unwrap(),
+            b"# Long\n\nA longer document."
+        );
+
+        let capacity = buffer.bytes.capacity();
+        assert!(matches!(
+            buffer.read_str(dir.join("short.md")).unwrap(),
+            Cow::Borrowed("# Short")
+        ));
+        assert_eq!(buffer.bytes.capacity(), capacity);
+        assert_eq!(
+            buffer.read_str(dir.join("invalid.md")).unwrap(),
+            "# \u{fffd}Invalid"
+        );
+        assert!(buffer.read(dir.join("missing.md")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
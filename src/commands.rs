@@ -3,21 +3,54 @@
 // See LICENSE file in repository root for full text.
 
 use crate::{
-    library::Library,
+    config::Config,
+    data::Data,
+    hooks::Stage,
+    hosting, href,
+    i18n::Message,
+    layout::Layouts,
+    library::{self, Kept, Library, LibraryHtml},
+    pdf,
     prompt::{self, PromptItem},
+    scripts::Scripts,
+    site,
+    style::Style,
+    theme, webmention,
+};
+use std::{
+    error, fs,
+    path::{Path, PathBuf},
+    process,
+    rc::Rc,
 };
-use std::{error, process};
 
 const LIBRARY_FILE: &str = ".whim.ron";
+const SEND_ACTION: &str = "send";
+const ALL_DOCUMENTS: &str = "all";
+const PDF_DIR: &str = "pdf";
+
+pub fn new(config: &Config) -> Result<(), Box<dyn error::Error>> {
+    if Path::new(LIBRARY_FILE).exists() {
+        // Nobody may have meant to overwrite the library if nobody is there
+        // to answer, so only an explicit `--yes` does without asking.
+        let yn = prompt::No::from_prompt_strict(
+            Message::LibraryExists.text(),
+            Some('?'),
+            prompt::NonInteractive::Fail,
+        )?;
 
-pub fn new() -> Result<(), Box<dyn error::Error>> {
-    let lib = Library::scan()?;
+        if yn == prompt::No::No {
+            return Ok(());
+        }
+    }
+
+    let mut lib = Library::scan(config.scan())?;
 
     match lib.documents().len() > 0 {
         true => {
             println!(
-                "whim found {} markdown documents in the current directory:",
-                lib.documents().len()
+                "{}",
+                Message::FoundDocuments.format(&[&lib.documents().len()])
             );
 
             for doc in lib.documents().keys() {
@@ -25,20 +58,39 @@ pub fn new() -> Result<(), Box<dyn error::Error>> {
             }
         }
         false => {
-            println!("whim found no markdown documents in the current directory")
+            println!("{}", Message::FoundNoDocuments.text())
         }
     }
 
-    let yn = prompt::Yes::from_prompt(
-        format!(
-            "create a new library with {} documents",
-            lib.documents().len()
-        ),
+    let yn = prompt::Yes::from_prompt_or(
+        Message::CreateLibrary.format(&[&lib.documents().len()]),
         Some('?'),
+        prompt::NonInteractive::AssumeYes,
     )?;
 
     match yn {
         prompt::Yes::Yes => {
+            let default = lib.site_mut().title();
+            let title =
+                String::from_prompt_with_default(Message::SiteTitle.text(), Some(':'), default)?;
+            lib.site_mut().set_title(title);
+
+            let related = usize::from_prompt_with_default_validated(
+                Message::RelatedCount.text(),
+                Some(':'),
+                lib.site().related().to_string(),
+                prompt::in_range(..=site::MAX_LISTED),
+            )?;
+            lib.site_mut().set_related(related);
+
+            let recent = usize::from_prompt_with_default_validated(
+                Message::RecentCount.text(),
+                Some(':'),
+                lib.site().recent().to_string(),
+                prompt::in_range(..=site::MAX_LISTED),
+            )?;
+            lib.site_mut().set_recent(recent);
+
             lib.save(LIBRARY_FILE)?;
             return Ok(());
         }
@@ -46,119 +98,257 @@ pub fn new() -> Result<(), Box<dyn error::Error>> {
     }
 }
 
-pub fn update() -> Result<(), Box<dyn error::Error>> {
-    let lib = open_lib();
-    let docs = lib.changed_docs();
+pub fn update(config: &Config) -> Result<(), Box<dyn error::Error>> {
+    let mut lib = open_lib();
+    let docs = lib.changed_docs(config.scan());
 
     match docs.len() {
         1.. => {
-            println!("{} documents have changed:", docs.len());
+            let chosen: Vec<String> = prompt::MultiSelect::new(&docs)
+                .all_by_default()
+                .prompt(Message::DocumentsChanged.format(&[&docs.len()]))?
+                .into_iter()
+                .map(|i| docs[i].to_owned())
+                .collect();
 
-            for d in docs.clone() {
-                println!("    {}", d);
-            }
-
-            let yn = prompt::Yes::from_prompt(
-                format!("update {} documents in library", docs.len()),
-                Some('?'),
-            )?;
+            if !chosen.is_empty() {
+                // Documents left out must still be seen as changed next time,
+                // so the commit is only recorded once none are left.
+                let commit = match chosen.len() == docs.len() {
+                    true => config.scan().head(),
+                    false => None,
+                };
 
-            match yn {
-                prompt::Yes::Yes => {
-                    let len = docs.len();
-                    lib.update()?.save(LIBRARY_FILE)?;
-                    println!("updated {} documents in library", len);
-                    Ok(())
-                }
-                prompt::Yes::No => {
-                    println!("updated 0 documents in library");
-                    Ok(())
-                }
+                let mut lib = lib.update_documents(&chosen)?;
+                lib.set_commit(commit);
+                lib.save(LIBRARY_FILE)?;
             }
+
+            println!(
+                "{}",
+                Style::Bold.paint(Message::UpdatedDocuments.format(&[&chosen.len()]))
+            );
+            Ok(())
         }
         _ => {
-            println!("no updates to make");
+            let commit = config.scan().head();
+
+            if lib.commit() != commit.as_deref() {
+                lib.set_commit(commit);
+                lib.save(LIBRARY_FILE)?;
+            }
+
+            println!("{}", Message::NoUpdates.text());
             return Ok(());
         }
     }
 }
 
-pub fn scan() -> Result<(), Box<dyn error::Error>> {
+pub fn scan(config: &Config) -> Result<(), Box<dyn error::Error>> {
     let mut lib = open_lib();
-    let docs = lib.scan_for_new()?;
+    let docs = lib.scan_for_new(config.scan())?;
 
     match docs.len() {
         1.. => {
-            println!("found {} documents not in the library:", docs.len());
+            let chosen: Vec<Rc<str>> = prompt::MultiSelect::new(&docs)
+                .all_by_default()
+                .prompt(Message::FoundNewDocuments.format(&[&docs.len()]))?
+                .into_iter()
+                .map(|i| docs[i].clone())
+                .collect();
 
-            for doc in docs.clone() {
-                println!("    {}", doc);
+            if chosen.is_empty() {
+                println!(
+                    "{}",
+                    Style::Bold.paint(Message::AddedDocuments.format(&[&0]))
+                );
+                return Ok(());
             }
 
-            let yn = prompt::Yes::from_prompt(
-                format!("add {} documents to library", docs.len()),
-                Some('?'),
-            )?;
-
-            match yn {
-                prompt::Yes::Yes => {
-                    for doc in docs.clone() {
-                        match lib.add_document(doc.as_ref()) {
-                            Ok(_) => println!("    added {}", doc),
-                            Err(_) => println!("    failed to add {}", doc),
-                        }
-                    }
-
-                    match lib.save(LIBRARY_FILE) {
-                        Ok(_) => println!("added {} documents to library", docs.len()),
-                        Err(_) => println!("could not update library with new documents"),
-                    }
-
-                    Ok(())
+            for doc in chosen.clone() {
+                match lib.add_document(doc.as_ref()) {
+                    Ok(_) => println!(
+                        "    {} {}",
+                        Style::Success.paint(Message::Added.text()),
+                        doc
+                    ),
+                    Err(_) => println!(
+                        "    {} {}",
+                        Style::Failure.paint(Message::FailedToAdd.text()),
+                        doc
+                    ),
                 }
-                prompt::Yes::No => todo!(),
             }
+
+            match lib.save(LIBRARY_FILE) {
+                Ok(_) => println!(
+                    "{}",
+                    Style::Bold.paint(Message::AddedDocuments.format(&[&chosen.len()]))
+                ),
+                Err(_) => println!(
+                    "{}",
+                    Style::Failure.paint(Message::CouldNotUpdateLibrary.text())
+                ),
+            }
+
+            Ok(())
         }
         _ => {
-            println!("found no documents not already in library");
+            println!("{}", Message::FoundNoNewDocuments.text());
             Ok(())
         }
     }
 }
 
-pub fn add(path: String) -> Result<(), Box<dyn error::Error>> {
+pub fn add(config: &Config, path: Option<String>) -> Result<(), Box<dyn error::Error>> {
     let mut lib = open_lib();
 
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let docs = lib.scan_for_new(config.scan())?;
+
+            if docs.is_empty() {
+                println!("{}", Message::FoundNoNewDocuments.text());
+                return Ok(());
+            }
+
+            match prompt::Select::new(&docs).prompt(Message::WhichDocument.text()) {
+                Ok(i) => docs[i].to_string(),
+                Err(prompt::Error::NoDefault) => {
+                    println!(
+                        "{}",
+                        Style::Failure.paint(Message::GiveDocumentToAdd.text())
+                    );
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    };
+
     match lib.add_document(path.clone()) {
         Ok(_) => (),
         Err(_) => {
-            println!("could not add '{}'", path);
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotAdd.format(&[&path]))
+            );
             return Ok(());
         }
     }
 
     match lib.save(LIBRARY_FILE) {
-        Ok(_) => println!("added '{}'", path),
-        Err(_) => println!("could not save library, add failed"),
+        Ok(_) => println!(
+            "{}",
+            Style::Success.paint(Message::AddedPath.format(&[&path]))
+        ),
+        Err(_) => println!("{}", Style::Failure.paint(Message::CouldNotSaveAdd.text())),
     }
 
     Ok(())
 }
 
-pub fn build(path: String) -> Result<(), Box<dyn error::Error>> {
-    let lib = open_lib();
+pub fn build(config: &Config, path: Option<String>) -> Result<(), Box<dyn error::Error>> {
+    let mut lib = open_lib();
 
-    let lib_html = match lib.gen_html() {
-        Ok(v) => v,
-        Err(_) => {
-            println!("could not read all documents for parsing");
-            return Ok(());
+    if let Some(site) = config.site() {
+        *lib.site_mut() = site.clone();
+    }
+
+    let path = match path.or_else(|| Some(config.output()?.to_string_lossy().into_owned())) {
+        Some(path) => path,
+        None => PathBuf::from_prompt_validated(
+            Message::OutputDirectory.text(),
+            Some(':'),
+            prompt::non_empty,
+        )?
+        .to_string_lossy()
+        .into_owned(),
+    };
+
+    // Pre-build hooks may change documents themselves, so they are given the
+    // documents changed before they run and the rest of the build looks again.
+    let hooks = config.hooks();
+    let pre_build = !hooks.commands(Stage::PreBuild).is_empty();
+
+    if pre_build
+        && hooks
+            .run(Stage::PreBuild, &path, &lib.changed_docs(config.scan()))
+            .is_err()
+    {
+        println!(
+            "{}",
+            Style::Failure.paint(Message::PreBuildHookFailed.text())
+        );
+        return Ok(());
+    }
+
+    let changed = lib.changed_docs(config.scan());
+
+    let lib_html = match site_html(config, &lib) {
+        Some(lib_html) => lib_html,
+        None => return Ok(()),
+    };
+
+    let lib_html = match config.hosting() {
+        Some(host) => {
+            let redirects = config
+                .aliases()
+                .iter()
+                .map(
+                    |(from, to)| match lib.documents().contains_key(to.as_str()) {
+                        true => (from.clone(), lib.doc_href(to)),
+                        false => (from.clone(), to.clone()),
+                    },
+                )
+                .collect::<Vec<_>>();
+
+            hosting::outputs(host, &redirects, lib_html.fingerprints())
+                .into_iter()
+                .fold(lib_html, |html, (href, contents)| {
+                    html.with_output(href, contents)
+                })
         }
+        None => lib_html,
     };
 
+    for collision in lib_html.case_collisions() {
+        log::warn!(
+            "'{}' differ only by case, only one will be kept on case-insensitive file systems",
+            collision.join("', '")
+        );
+    }
+
+    log::info!("writing HTML to '{}'", path);
+
     match lib_html.write(path.clone()) {
-        Ok(_) => println!("wrote HTML to '{}'", path),
-        Err(_) => println!("could not write HTML to '{}", path),
+        Ok(_) => println!(
+            "{}",
+            Style::Success.paint(Message::WroteHtml.format(&[&path]))
+        ),
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotWriteHtml.format(&[&path]))
+            );
+            return Ok(());
+        }
+    }
+
+    if config.webmentions() {
+        queue_mentions(&lib, &changed);
+    }
+
+    if config
+        .hooks()
+        .run(Stage::PostBuild, &path, &changed)
+        .is_err()
+    {
+        println!(
+            "{}",
+            Style::Failure.paint(Message::PostBuildHookFailed.text())
+        );
     }
 
     Ok(())
@@ -166,11 +356,508 @@ pub fn build(path: String) -> Result<(), Box<dyn error::Error>> {
 
 #[inline]
 fn open_lib() -> Library {
-    match Library::open(LIBRARY_FILE) {
+    let mut lib = match Library::open(LIBRARY_FILE) {
         Ok(l) => l,
+        Err(library::Error::DeserializationError) => {
+            println!("{}", Style::Failure.paint(Message::InvalidLibrary.text()));
+            process::exit(0);
+        }
         Err(_) => {
-            println!("whim could not open a library in the current directory, you may need to create one with `whim new`");
+            println!("{}", Message::NoLibrary.text());
             process::exit(0);
         }
+    };
+
+    log::info!(
+        "opened library of {} documents hashed with {:?}",
+        lib.documents().len(),
+        lib.hash_algorithm()
+    );
+
+    let migrated = lib.migrate_hashes();
+
+    if migrated > 0 {
+        match lib.save(LIBRARY_FILE) {
+            Ok(_) => println!(
+                "{}",
+                Message::Rehashed.format(&[&migrated, &format!("{:?}", lib.hash_algorithm())])
+            ),
+            Err(_) => println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotSaveRehashed.text())
+            ),
+        }
+    }
+
+    lib
+}
+
+pub fn merge(path: Option<String>) -> Result<(), Box<dyn error::Error>> {
+    let path = match path {
+        Some(path) => path,
+        None => match PathBuf::from_prompt_validated(
+            Message::LibraryToMerge.text(),
+            Some(':'),
+            prompt::exists,
+        ) {
+            Ok(path) => path.to_string_lossy().into_owned(),
+            Err(_) => {
+                println!("{}", Message::MergeWhichLibrary.text());
+                return Ok(());
+            }
+        },
+    };
+
+    let mut lib = open_lib();
+    let other = match Library::open(&path) {
+        Ok(other) => other,
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadMerged.format(&[&path]))
+            );
+            return Ok(());
+        }
+    };
+
+    let before = lib.documents().len();
+    let conflicts = lib.merge(other);
+
+    for conflict in &conflicts {
+        let kept = match conflict.kept {
+            Kept::Ours => Message::KeptOurs,
+            Kept::Theirs => Message::KeptTheirs,
+        };
+
+        println!(
+            "    {} {}",
+            Style::Warning.paint(kept.text()),
+            conflict.path
+        );
+    }
+
+    match lib.save(LIBRARY_FILE) {
+        Ok(_) => println!(
+            "{}",
+            Style::Bold.paint(
+                Message::Merged.format(&[&(lib.documents().len() - before), &conflicts.len()])
+            )
+        ),
+        Err(_) => println!(
+            "{}",
+            Style::Failure.paint(Message::CouldNotSaveMerged.text())
+        ),
+    }
+
+    Ok(())
+}
+
+pub fn webmention(action: Option<String>) -> Result<(), Box<dyn error::Error>> {
+    let mut queued = match webmention::queued(webmention::MENTIONS_FILE) {
+        Ok(queued) => queued,
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadMentions.text())
+            );
+            return Ok(());
+        }
+    };
+
+    if queued.is_empty() {
+        println!("{}", Message::NoMentions.text());
+        return Ok(());
+    }
+
+    if action.as_deref() != Some(SEND_ACTION) {
+        for mention in &queued {
+            println!("    {} -> {}", mention.source, mention.target);
+        }
+
+        return Ok(());
+    }
+
+    let mut sent = match webmention::queued(webmention::SENT_FILE) {
+        Ok(sent) => sent,
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadMentions.text())
+            );
+            return Ok(());
+        }
+    };
+
+    let total = queued.len();
+
+    queued.retain(|mention| match webmention::send(mention) {
+        Ok(_) => {
+            println!(
+                "    {} {}",
+                Style::Success.paint(Message::Sent.text()),
+                mention.target
+            );
+            sent.insert(mention.clone());
+            false
+        }
+        Err(_) => {
+            println!(
+                "    {} {}",
+                Style::Failure.paint(Message::FailedToSend.text()),
+                mention.target
+            );
+            true
+        }
+    });
+
+    webmention::save(webmention::MENTIONS_FILE, &queued)?;
+    webmention::save(webmention::SENT_FILE, &sent)?;
+    println!(
+        "{}",
+        Style::Bold.paint(Message::SentMentions.format(&[&(total - queued.len()), &total]))
+    );
+
+    Ok(())
+}
+
+pub fn export_pdf(
+    config: &Config,
+    doc: Option<String>,
+    path: Option<String>,
+) -> Result<(), Box<dyn error::Error>> {
+    let mut lib = open_lib();
+
+    if let Some(site) = config.site() {
+        *lib.site_mut() = site.clone();
+    }
+
+    let mut docs = lib
+        .documents()
+        .keys()
+        .map(|doc| doc.to_string())
+        .collect::<Vec<_>>();
+    docs.sort();
+
+    let doc = match doc {
+        Some(doc) => doc,
+        None => {
+            let mut options = vec![ALL_DOCUMENTS.to_owned()];
+            options.extend(docs.iter().cloned());
+            // Every document is exported unless another is chosen.
+            let chosen = prompt::Select::new(&options)
+                .with_default(0)
+                .prompt(Message::ExportWhichDocument.text())?;
+            options[chosen].clone()
+        }
+    };
+
+    let docs = match doc.as_str() {
+        ALL_DOCUMENTS => docs,
+        _ => {
+            let doc = href::from_path(Path::new(&doc));
+
+            match lib.documents().contains_key(doc.as_str()) {
+                true => vec![doc],
+                false => {
+                    println!(
+                        "{}",
+                        Style::Failure.paint(Message::NotInLibrary.format(&[&doc]))
+                    );
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    // Protected pages are built encrypted, so would print as nothing but the
+    // form asking for their passphrase.
+    let docs = docs
+        .into_iter()
+        .filter(|doc| match lib.is_protected(doc) {
+            Ok(false) => true,
+            Ok(true) => {
+                log::warn!("leaving protected '{}' out of the export", doc);
+                false
+            }
+            Err(_) => {
+                log::warn!("leaving '{}' out of the export, it could not be read", doc);
+                false
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let renderer = match pdf::find_renderer(config.pdf_renderer()) {
+        Some(renderer) => renderer,
+        None => {
+            println!("{}", Style::Failure.paint(Message::NoPdfRenderer.text()));
+            return Ok(());
+        }
+    };
+
+    let lib_html = match site_html(config, &lib) {
+        Some(lib_html) => lib_html,
+        None => return Ok(()),
+    };
+
+    // Removed whenever this returns, written or not.
+    let site_dir = pdf::SiteDir::temp();
+    let site = site_dir.path();
+    log::info!("writing HTML to '{}'", site.display());
+
+    if lib_html.write(site).is_err() {
+        println!(
+            "{}",
+            Style::Failure.paint(Message::CouldNotWriteHtml.format(&[&site.display()]))
+        );
+        return Ok(());
+    }
+
+    let path = PathBuf::from(path.unwrap_or_else(|| PDF_DIR.to_owned()));
+    let mut exported = 0;
+
+    for doc in &docs {
+        let href = lib.doc_href(doc);
+        let pdf = path.join(&href).with_extension("pdf");
+
+        match pdf::render(&renderer, &site.join(&href), &pdf) {
+            Ok(_) => {
+                exported += 1;
+                println!(
+                    "    {} {}",
+                    Style::Success.paint(Message::Exported.text()),
+                    pdf.display()
+                );
+            }
+            Err(_) => println!(
+                "    {} {}",
+                Style::Failure.paint(Message::FailedToExport.text()),
+                doc
+            ),
+        }
+    }
+
+    println!(
+        "{}",
+        Style::Bold.paint(Message::ExportedPdfs.format(&[&exported, &docs.len(), &path.display()]))
+    );
+
+    Ok(())
+}
+
+pub fn epub(config: &Config, params: Vec<String>) -> Result<(), Box<dyn error::Error>> {
+    let mut lib = open_lib();
+
+    if let Some(site) = config.site() {
+        *lib.site_mut() = site.clone();
+    }
+
+    let mut params = params.into_iter();
+    let path = params
+        .next()
+        .unwrap_or_else(|| format!("{}.epub", href::slugify(&lib.site().title())));
+
+    let docs = params
+        .map(|doc| href::from_path(Path::new(&doc)))
+        .collect::<Vec<_>>();
+
+    if let Some(doc) = docs
+        .iter()
+        .find(|doc| !lib.documents().contains_key(doc.as_str()))
+    {
+        println!(
+            "{}",
+            Style::Failure.paint(Message::NotInLibrary.format(&[doc]))
+        );
+        return Ok(());
+    }
+
+    let data = match load_data(config) {
+        Some(data) => data,
+        None => return Ok(()),
+    };
+
+    let scripts = match Scripts::load(config.scripts()) {
+        Ok(scripts) => scripts.with_data(&data),
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotLoadScripts.text())
+            );
+            return Ok(());
+        }
+    };
+
+    let docs = docs.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let book = match lib.gen_epub(&docs, &scripts) {
+        Ok(book) => book,
+        Err(library::Error::ScriptError) => {
+            println!("{}", Style::Failure.paint(Message::ScriptFailed.text()));
+            return Ok(());
+        }
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadDocuments.text())
+            );
+            return Ok(());
+        }
+    };
+
+    let stylesheet = theme::overrides(config.theme())
+        .unwrap_or_default()
+        .into_iter()
+        .find(|(href, _)| href == theme::STYLESHEET_HREF)
+        .and_then(|(_, path)| fs::read_to_string(path).ok());
+
+    let book = match stylesheet {
+        Some(stylesheet) => book.with_stylesheet(stylesheet),
+        None => book,
+    };
+
+    match book.write(&path) {
+        Ok(_) => println!(
+            "{}",
+            Style::Success.paint(Message::WroteEpub.format(&[&book.chapters().len(), &path]))
+        ),
+        Err(_) => println!(
+            "{}",
+            Style::Failure.paint(Message::CouldNotWriteEpub.format(&[&path]))
+        ),
+    }
+
+    Ok(())
+}
+
+/// Reads the configured data files, printing why if they cannot be.
+fn load_data(config: &Config) -> Option<Data> {
+    match Data::load(config.data()) {
+        Ok(data) => Some(data),
+        Err(_) => {
+            println!("{}", Style::Failure.paint(Message::CouldNotReadData.text()));
+            None
+        }
+    }
+}
+
+/// Generates the site's HTML with the configured data, scripts, layouts, and
+/// theme overrides, fingerprinting assets if the site is set to, printing why
+/// if it cannot be.
+fn site_html(config: &Config, lib: &Library) -> Option<LibraryHtml> {
+    let data = load_data(config)?;
+
+    let scripts = match Scripts::load(config.scripts()) {
+        Ok(scripts) => scripts.with_data(&data),
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotLoadScripts.text())
+            );
+            return None;
+        }
+    };
+
+    let layouts = match Layouts::load(config.theme()) {
+        Ok(layouts) => layouts,
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadTheme.text())
+            );
+            return None;
+        }
+    };
+
+    let lib_html = match lib.gen_html(&scripts, &layouts, &data) {
+        Ok(v) => v,
+        Err(library::Error::FrontMatterError) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::InvalidFrontMatter.text())
+            );
+            return None;
+        }
+        Err(library::Error::ScriptError) => {
+            println!("{}", Style::Failure.paint(Message::ScriptFailed.text()));
+            return None;
+        }
+        Err(library::Error::ImageError) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotOptimizeImages.text())
+            );
+            return None;
+        }
+        Err(library::Error::EncryptionError) => {
+            println!("{}", Style::Failure.paint(Message::CouldNotProtect.text()));
+            return None;
+        }
+        Err(library::Error::HrefCollisionError) => {
+            println!("{}", Style::Failure.paint(Message::HrefCollision.text()));
+            return None;
+        }
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadDocuments.text())
+            );
+            return None;
+        }
+    };
+
+    let lib_html = match theme::overrides(config.theme()) {
+        Ok(files) => lib_html.with_overrides(files),
+        Err(_) => {
+            println!(
+                "{}",
+                Style::Failure.paint(Message::CouldNotReadTheme.text())
+            );
+            return None;
+        }
+    };
+
+    match lib.site().fingerprint() {
+        true => match lib_html.with_fingerprints(lib.site().base_url()) {
+            Ok(lib_html) => Some(lib_html),
+            Err(_) => {
+                println!(
+                    "{}",
+                    Style::Failure.paint(Message::CouldNotReadTheme.text())
+                );
+                None
+            }
+        },
+        false => Some(lib_html),
+    }
+}
+
+/// Adds mentions of external links in the given changed documents to the queue
+/// sent by `whim webmention send`, leaving out those already sent.
+fn queue_mentions(lib: &Library, changed: &[&str]) {
+    if lib.site().base_url().is_none() {
+        log::warn!("webmentions need the site's base URL to be set, none were queued");
+        return;
+    }
+
+    let queued = webmention::queued(webmention::MENTIONS_FILE).and_then(|mut queued| {
+        let sent = webmention::queued(webmention::SENT_FILE)?;
+        let before = queued.len();
+
+        queued.extend(
+            lib.mentions(changed)
+                .into_iter()
+                .filter(|mention| !sent.contains(mention)),
+        );
+        webmention::save(webmention::MENTIONS_FILE, &queued)?;
+        Ok(queued.len() - before)
+    });
+
+    match queued {
+        Ok(0) => (),
+        Ok(n) => println!("{}", Message::QueuedMentions.format(&[&n])),
+        Err(_) => println!(
+            "{}",
+            Style::Failure.paint(Message::CouldNotQueueMentions.text())
+        ),
     }
 }
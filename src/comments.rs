@@ -0,0 +1,93 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Comments embedded at the bottom of document pages from a third party
+//! service. Documents may opt out with `comments = false` in their front
+//! matter.
+
+use crate::escape;
+use serde::{Deserialize, Serialize};
+
+/// A comment service and its settings, see each service's documentation for
+/// where to find them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Comments {
+    /// [giscus](https://giscus.app), comments kept in GitHub Discussions.
+    Giscus {
+        repo: String,
+        repo_id: String,
+        category: String,
+        category_id: String,
+    },
+
+    /// [utterances](https://utteranc.es), comments kept in GitHub issues.
+    Utterances { repo: String },
+
+    /// A self hosted [Isso](https://isso-comments.de) server.
+    Isso { url: String },
+}
+
+impl Comments {
+    /// Creates the HTML embedding the comment thread of a page, each page
+    /// being given its own thread by its path.
+    #[must_use]
+    pub fn html(&self) -> String {
+        let embed = match self {
+            Comments::Giscus {
+                repo,
+                repo_id,
+                category,
+                category_id,
+            } => format!(
+                "<script src=\"https://giscus.app/client.js\" data-repo=\"{}\" \
+                data-repo-id=\"{}\" data-category=\"{}\" data-category-id=\"{}\" \
+                data-mapping=\"pathname\" data-reactions-enabled=\"1\" \
+                data-theme=\"preferred_color_scheme\" crossorigin=\"anonymous\" async></script>",
+                escape::html(repo),
+                escape::html(repo_id),
+                escape::html(category),
+                escape::html(category_id)
+            ),
+            Comments::Utterances { repo } => format!(
+                "<script src=\"https://utteranc.es/client.js\" repo=\"{}\" \
+                issue-term=\"pathname\" theme=\"preferred-color-scheme\" \
+                crossorigin=\"anonymous\" async></script>",
+                escape::html(repo)
+            ),
+            Comments::Isso { url } => {
+                let url = escape::html(url.trim_end_matches('/'));
+                format!(
+                    "<script data-isso=\"{}/\" src=\"{}/js/embed.min.js\"></script>\
+                    <section id=\"isso-thread\"></section>",
+                    url, url
+                )
+            }
+        };
+
+        format!("<section class=\"comments\">{}</section>", embed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_test() {
+        let utterances = Comments::Utterances {
+            repo: "an-prata/\"notes\"".to_owned(),
+        };
+        let isso = Comments::Isso {
+            url: "https://comments.example.com/".to_owned(),
+        };
+
+        assert!(utterances
+            .html()
+            .contains("repo=\"an-prata/&quot;notes&quot;\""));
+        assert!(isso
+            .html()
+            .contains("src=\"https://comments.example.com/js/embed.min.js\""));
+        assert!(isso.html().starts_with("<section class=\"comments\">"));
+    }
+}
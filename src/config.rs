@@ -0,0 +1,378 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Project configuration, read from `.whim.toml`. This is kept apart from the
+//! library's document database in `.whim.ron` so that it may be edited by hand
+//! and kept in version control. Every setting is optional and a missing file
+//! is the same as an empty one.
+
+use crate::{data, diagnostic::Diagnostic, git, hooks::Hooks, hosting::Host, site::Site, theme};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+    result,
+};
+
+/// Path of the project configuration, relative to the library's directory.
+pub const CONFIG_FILE: &str = ".whim.toml";
+
+/// Hint given with errors in the configuration.
+const CONFIG_HINT: &str = "see the README for the settings a configuration may contain";
+
+/// Settings of a project, see the [module] documentation.
+///
+/// [module]: crate::config
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Site settings, used in place of those kept in the library if given.
+    site: Option<Site>,
+
+    /// Which markdown files are found when scanning for documents.
+    scan: Scan,
+
+    /// Directory written to by `whim build` when none is given.
+    output: Option<PathBuf>,
+
+    /// Commands run before and after building.
+    hooks: Hooks,
+
+    /// Paths of scripts which transform each document's page, in order.
+    scripts: Vec<PathBuf>,
+
+    /// Directory of files overriding the default theme.
+    theme: Option<PathBuf>,
+
+    /// Directory of data files given to layouts and scripts.
+    data: Option<PathBuf>,
+
+    /// Static host to write configuration for.
+    hosting: Option<Host>,
+
+    /// Hrefs which redirect to a document, given by its path, or to another
+    /// href or URL.
+    aliases: BTreeMap<String, String>,
+
+    /// Whether to queue webmentions of external links in changed documents.
+    webmentions: bool,
+
+    /// Program used by `whim export-pdf`, found automatically if not given.
+    pdf_renderer: Option<PathBuf>,
+}
+
+impl Config {
+    /// Reads a [`Config`] from the TOML file at the given path, or returns the
+    /// default [`Config`] if there is no such file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    ///
+    /// [`Config`]: Config
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(s) => toml::from_str(&s).map_err(|e| {
+                let diagnostic = match e.span() {
+                    Some(span) => Diagnostic::from_span(path, &s, span, e.message()),
+                    None => Diagnostic::new(path, &s, 1, 1, e.message()),
+                };
+
+                log::error!("{}", diagnostic.with_hint(CONFIG_HINT));
+                Error::DeserializationError
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(_) => Err(Error::FileReadError),
+        }
+    }
+
+    /// Gets the site settings to use in place of the library's, if any.
+    #[inline]
+    #[must_use]
+    pub fn site(&self) -> Option<&Site> {
+        self.site.as_ref()
+    }
+
+    /// Gets which markdown files are found when scanning for documents.
+    #[inline]
+    #[must_use]
+    pub fn scan(&self) -> &Scan {
+        &self.scan
+    }
+
+    /// Gets the directory to build to when none is given, if one is set.
+    #[inline]
+    #[must_use]
+    pub fn output(&self) -> Option<&Path> {
+        self.output.as_deref()
+    }
+
+    /// Gets the commands run before and after building.
+    #[inline]
+    #[must_use]
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    /// Gets the paths of scripts which transform each document's page.
+    #[inline]
+    #[must_use]
+    pub fn scripts(&self) -> &[PathBuf] {
+        &self.scripts
+    }
+
+    /// Gets the static host to write configuration for, if any.
+    #[inline]
+    #[must_use]
+    pub fn hosting(&self) -> Option<Host> {
+        self.hosting
+    }
+
+    /// Gets aliases, as a map of hrefs to the document path, href, or URL they
+    /// redirect to.
+    #[inline]
+    #[must_use]
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    /// Returns true if webmentions should be queued for external links in
+    /// changed documents, see the [`webmention`] module.
+    ///
+    /// [`webmention`]: crate::webmention
+    #[inline]
+    #[must_use]
+    pub fn webmentions(&self) -> bool {
+        self.webmentions
+    }
+
+    /// Gets the directory of files overriding the default theme, see the
+    /// [`theme`] module.
+    ///
+    /// [`theme`]: crate::theme
+    #[inline]
+    #[must_use]
+    pub fn theme(&self) -> &Path {
+        self.theme
+            .as_deref()
+            .unwrap_or(Path::new(theme::OVERRIDES_DIR))
+    }
+
+    /// Gets the directory of data files given to layouts and scripts, see the
+    /// [`data`] module.
+    ///
+    /// [`data`]: crate::data
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &Path {
+        self.data.as_deref().unwrap_or(Path::new(data::DIR))
+    }
+
+    /// Gets the program to render PDFs with, if one is set, see the [`pdf`]
+    /// module.
+    ///
+    /// [`pdf`]: crate::pdf
+    #[inline]
+    #[must_use]
+    pub fn pdf_renderer(&self) -> Option<&Path> {
+        self.pdf_renderer.as_deref()
+    }
+}
+
+/// Glob patterns of files to include in and exclude from scans for documents,
+/// relative to the library's directory.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scan {
+    /// Files matching any of these patterns are found, "**/*.md" by default.
+    include: Vec<String>,
+
+    /// Files matching any of these patterns are not found, even if included.
+    exclude: Vec<String>,
+
+    /// Whether to ask git for files and changes rather than walking the
+    /// directory and hashing every document.
+    git: bool,
+}
+
+impl Scan {
+    /// Finds every file matching an included pattern but no excluded one,
+    /// sorted and without duplicates. When using git, files it ignores are
+    /// not found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the patterns are invalid.
+    pub fn paths(&self) -> result::Result<Vec<PathBuf>, glob::PatternError> {
+        let exclude = patterns(&self.exclude)?;
+        let mut paths = BTreeSet::new();
+
+        if let Some(files) = self.git_files(git::files) {
+            let include = patterns(&self.include)?;
+
+            paths.extend(files.into_iter().filter(|path| {
+                include.iter().any(|p| p.matches_path(path))
+                    && !exclude.iter().any(|p| p.matches_path(path))
+            }));
+
+            return Ok(paths.into_iter().collect());
+        }
+
+        for pattern in &self.include {
+            paths.extend(
+                glob::glob(pattern)?
+                    .filter_map(result::Result::ok)
+                    .filter(|path| !exclude.iter().any(|p| p.matches_path(path))),
+            );
+        }
+
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Gets the files git reports as changed since the given commit, or
+    /// [`None`] if git is not used or no commit is given, in which case any
+    /// file may have changed.
+    ///
+    /// [`None`]: None
+    #[must_use]
+    pub fn changed(&self, since: Option<&str>) -> Option<Vec<PathBuf>> {
+        let since = since?;
+        self.git_files(|dir| git::changed(dir, since))
+    }
+
+    /// Gets the hash of the commit checked out, or [`None`] if git is not
+    /// used.
+    ///
+    /// [`None`]: None
+    #[must_use]
+    pub fn head(&self) -> Option<String> {
+        self.git_files(git::head)
+    }
+
+    /// Asks git with the given function if git is used, warning and returning
+    /// [`None`] if it fails.
+    ///
+    /// [`None`]: None
+    fn git_files<T>(&self, ask: impl FnOnce(&'static str) -> git::Result<T>) -> Option<T> {
+        if !self.git {
+            return None;
+        }
+
+        match ask(".") {
+            Ok(files) => Some(files),
+            Err(e) => {
+                log::warn!("could not ask git for files ({}), scanning without it", e);
+                None
+            }
+        }
+    }
+}
+
+/// Compiles each of the given glob patterns.
+fn patterns(patterns: &[String]) -> result::Result<Vec<glob::Pattern>, glob::PatternError> {
+    patterns.iter().map(|p| glob::Pattern::new(p)).collect()
+}
+
+impl Default for Scan {
+    fn default() -> Self {
+        Self {
+            include: vec!["**/*.md".to_owned()],
+            exclude: Vec::new(),
+            git: false,
+        }
+    }
+}
+
+/// The result type of configuration related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while reading a [`Config`].
+///
+/// [`Config`]: Config
+#[derive(Debug)]
+pub enum Error {
+    /// I/O failure to read the file.
+    FileReadError,
+
+    /// The file is not valid TOML or has invalid settings.
+    DeserializationError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::Stage;
+
+    #[test]
+    fn config_test() {
+        let config: Config = toml::from_str(
+            r#"
+            output = "public"
+            scripts = ["banner.rhai"]
+            hosting = "Netlify"
+
+            [aliases]
+            "old.html" = "blog/new.md"
+
+            [site]
+            title = "Notes"
+            link_mode = "Absolute"
+
+            [scan]
+            exclude = ["drafts/**"]
+
+            [hooks]
+            post_build = ["npx tailwindcss -o public/tw.css"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.output(), Some(Path::new("public")));
+        assert_eq!(config.scripts(), [PathBuf::from("banner.rhai")]);
+        assert_eq!(config.hosting(), Some(Host::Netlify));
+        assert_eq!(config.aliases()["old.html"], "blog/new.md");
+        assert_eq!(config.site().unwrap().title(), "Notes");
+        assert_eq!(config.scan().include, vec!["**/*.md"]);
+        assert_eq!(config.scan().exclude, vec!["drafts/**"]);
+        assert_eq!(
+            config.hooks().commands(Stage::PostBuild),
+            ["npx tailwindcss -o public/tw.css"]
+        );
+
+        assert_eq!(toml::from_str::<Config>("").unwrap(), Config::default());
+        assert!(toml::from_str::<Config>("output = 1").is_err());
+    }
+
+    #[test]
+    fn scan_test() {
+        let dir = std::env::temp_dir().join("whim-scan-test");
+        fs::create_dir_all(dir.join("drafts")).unwrap();
+
+        for file in ["a.md", "b.txt", "drafts/c.md"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let root = dir.to_string_lossy();
+        let scan = Scan {
+            include: vec![format!("{}/**/*.md", root), format!("{}/a.md", root)],
+            exclude: vec![format!("{}/drafts/**", root)],
+            git: false,
+        };
+
+        assert_eq!(scan.paths().unwrap(), vec![dir.join("a.md")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Structured data kept in JSON, TOML, or YAML files in the library's data
+//! directory, `data` unless configured otherwise. Each file's data is named
+//! by the file's name without its extension, so a `projects.toml` is given
+//! to layouts as `{{ data.projects... }}` and to scripts as `data.projects`.
+
+use serde_json::{Map, Value};
+use std::{error, fmt, fs, path::Path, result};
+
+/// Directory, relative to the library, holding data files when none is
+/// configured.
+pub const DIR: &str = "data";
+
+/// Data of every data file, see the [module] documentation.
+///
+/// [module]: crate::data
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Data {
+    files: Map<String, Value>,
+}
+
+impl Data {
+    /// Reads every data file in the given directory, which may not exist.
+    /// Files with any extension other than `json`, `toml`, `yaml`, or `yml`
+    /// are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, logging which file and why, if a data file could not
+    /// be read or parsed.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(Self::default()),
+        };
+
+        let mut files = Map::new();
+
+        for path in entries.filter_map(|e| Some(e.ok()?.path())) {
+            let (name, ext) = match (path.file_stem(), path.extension()) {
+                (Some(name), Some(ext)) => (name.to_string_lossy(), ext.to_string_lossy()),
+                _ => continue,
+            };
+
+            let parse: fn(&str) -> result::Result<Value, String> = match ext.as_ref() {
+                "json" => |s| serde_json::from_str(s).map_err(|e| e.to_string()),
+                "toml" => |s| toml::from_str(s).map_err(|e| e.to_string()),
+                "yaml" | "yml" => |s| serde_yaml::from_str(s).map_err(|e| e.to_string()),
+                _ => continue,
+            };
+
+            let source = fs::read_to_string(&path).map_err(|_| Error::FileReadError)?;
+            let value = parse(&source).map_err(|e| {
+                log::error!("could not parse '{}': {}", path.display(), e);
+                Error::DeserializationError
+            })?;
+
+            log::debug!("loaded data '{}' from '{}'", name, path.display());
+            files.insert(name.into_owned(), value);
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Gets the data of each data file, keyed by its name.
+    #[cfg(any(feature = "scripting", test))]
+    #[inline]
+    #[must_use]
+    pub fn files(&self) -> &Map<String, Value> {
+        &self.files
+    }
+
+    /// Gets the value at the given path, names of data files, keys of tables,
+    /// and indices of lists being separated by '.', e.g. `projects.0.name`.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        let mut keys = path.split('.');
+        let value = self.files.get(keys.next()?)?;
+
+        keys.try_fold(value, |value, key| match value {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+            Value::Object(table) => table.get(key),
+            _ => None,
+        })
+    }
+
+    /// Renders the value at the given path as it is written into a layout.
+    /// Strings are written as they are, so may hold HTML, and lists and tables
+    /// are written as JSON.
+    #[must_use]
+    pub fn render(&self, path: &str) -> Option<String> {
+        Some(match self.get(path)? {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            value => value.to_string(),
+        })
+    }
+}
+
+/// The result type of data related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while loading data files.
+#[derive(Debug)]
+pub enum Error {
+    /// I/O failure to read a data file.
+    FileReadError,
+
+    /// A data file is not valid JSON, TOML, or YAML.
+    DeserializationError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_test() {
+        let dir = std::env::temp_dir().join("whim-data-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("site.json"), r#"{"motto": "<em>Hi</em>"}"#).unwrap();
+        fs::write(
+            dir.join("projects.toml"),
+            "[[list]]\nname = \"whim\"\nstars = 3",
+        )
+        .unwrap();
+        fs::write(dir.join("nav.yaml"), "- home\n- about\n").unwrap();
+        fs::write(dir.join("notes.txt"), "skipped").unwrap();
+
+        let data = Data::load(&dir).unwrap();
+
+        assert_eq!(data.files().len(), 3);
+        assert_eq!(data.render("site.motto").as_deref(), Some("<em>Hi</em>"));
+        assert_eq!(data.render("projects.list.0.name").as_deref(), Some("whim"));
+        assert_eq!(data.render("projects.list.0.stars").as_deref(), Some("3"));
+        assert_eq!(data.render("nav").as_deref(), Some(r#"["home","about"]"#));
+        assert_eq!(data.get("nav.2"), None);
+        assert!(Data::load(dir.join("missing")).unwrap().files().is_empty());
+
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        assert!(Data::load(&dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Descriptions of errors in files read by whim, showing where in the file the
+//! error is along with the offending line and, where there is one, a hint on
+//! how to fix it, e.g.
+//!
+//! ```text
+//! invalid type: integer `1`, expected path string
+//!   --> .whim.toml:2:10
+//!   |
+//! 2 | output = 1
+//!   |          ^
+//!   = hint: see the README for the settings a configuration may contain
+//! ```
+
+use std::{
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// An error at a position in a file, see the [module] documentation.
+///
+/// [module]: crate::diagnostic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    path: PathBuf,
+    message: String,
+    line: usize,
+    column: usize,
+    length: usize,
+    snippet: String,
+    hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates a new [`Diagnostic`] for the given line and column of the given
+    /// file contents, both counted from 1.
+    ///
+    /// [`Diagnostic`]: Diagnostic
+    #[must_use]
+    pub fn new(
+        path: impl AsRef<Path>,
+        source: &str,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        let line = line.max(1);
+        let snippet = source
+            .lines()
+            .nth(line - 1)
+            .unwrap_or_default()
+            .trim_end_matches('\r')
+            .to_owned();
+
+        Self {
+            path: path.as_ref().to_owned(),
+            message: message.into(),
+            line,
+            column: column.clamp(1, snippet.chars().count() + 1),
+            length: 1,
+            snippet,
+            hint: None,
+        }
+    }
+
+    /// Creates a new [`Diagnostic`] for the given span of bytes in the given
+    /// file contents. Spans over several lines are only marked on the first.
+    ///
+    /// [`Diagnostic`]: Diagnostic
+    #[must_use]
+    pub fn from_span(
+        path: impl AsRef<Path>,
+        source: &str,
+        span: Range<usize>,
+        message: impl Into<String>,
+    ) -> Self {
+        let start = floor_char_boundary(source, span.start);
+        let end = floor_char_boundary(source, span.end).max(start);
+
+        let before = &source[..start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = source[line_start..start].chars().count() + 1;
+
+        let marked = source[start..end].split('\n').next().unwrap_or_default();
+
+        Self {
+            length: marked.chars().count().max(1),
+            ..Self::new(path, source, line, column, message)
+        }
+    }
+
+    /// Adds a hint on how to fix the error.
+    #[must_use]
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(
+            f,
+            "{} --> {}:{}:{}",
+            gutter,
+            self.path.display(),
+            self.line,
+            self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", number, self.snippet)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.length)
+        )?;
+
+        match &self.hint {
+            Some(hint) => write!(f, "\n{} = hint: {}", gutter, hint),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Moves the given byte index back to the start of the character it is in,
+/// clamping it to the length of the given string.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostic_test() {
+        let source = "[site]\r\noutput = 1\n";
+        let diagnostic = Diagnostic::from_span(".whim.toml", source, 17..18, "expected a string")
+            .with_hint("quote the path");
+
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 10));
+        assert_eq!(
+            diagnostic.to_string(),
+            [
+                "expected a string",
+                "  --> .whim.toml:2:10",
+                "  |",
+                "2 | output = 1",
+                "  |          ^",
+                "  = hint: quote the path",
+            ]
+            .join("\n")
+        );
+
+        let diagnostic = Diagnostic::from_span("a", "ab\ncd", 1..5, "x");
+        assert_eq!((diagnostic.line, diagnostic.length), (1, 1));
+
+        // Positions past the end of the file are kept within it.
+        let diagnostic = Diagnostic::new("a", "ab", 4, 9, "x");
+        assert_eq!((diagnostic.snippet.as_str(), diagnostic.column), ("", 1));
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! EPUB export of the library. Documents are bundled as chapters of a single
+//! EPUB 3 book, with a table of contents and metadata from the site settings.
+//! Chapters keep the hrefs their pages have on the site so that links between
+//! documents in the book still work.
+
+use crate::{escape, href};
+use std::{
+    error, fmt,
+    fs::File,
+    io::{self, Write},
+    path::Path,
+    result,
+};
+use time::format_description::well_known::Rfc3339;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+/// Media type of an EPUB, which must be the first, uncompressed, file of one.
+const MIMETYPE: &str = "application/epub+zip";
+
+/// Directory of the book's content within the archive.
+const CONTENT_DIR: &str = "OEBPS";
+
+/// Points readers to the package document.
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+/// An EPUB book made of chapters in reading order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Book {
+    title: String,
+    lang: String,
+    identifier: String,
+    modified: time::OffsetDateTime,
+    author: Option<String>,
+    stylesheet: String,
+    chapters: Vec<Chapter>,
+}
+
+/// A single chapter of a [`Book`], being the HTML content of a document.
+///
+/// [`Book`]: Book
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chapter {
+    pub href: String,
+    pub title: String,
+    pub content: String,
+}
+
+impl Book {
+    /// Creates a new [`Book`] without any chapters, `identifier` being a URL
+    /// or URN unique to the book and `modified` when it last changed.
+    ///
+    /// [`Book`]: Book
+    #[must_use]
+    pub fn new(
+        title: impl Into<String>,
+        lang: impl Into<String>,
+        identifier: impl Into<String>,
+        modified: time::OffsetDateTime,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            lang: lang.into(),
+            identifier: identifier.into(),
+            modified,
+            author: None,
+            stylesheet: String::new(),
+            chapters: Vec::new(),
+        }
+    }
+
+    /// Sets the author the book is credited to.
+    #[must_use]
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Sets the stylesheet of every chapter.
+    #[must_use]
+    pub fn with_stylesheet(mut self, stylesheet: impl Into<String>) -> Self {
+        self.stylesheet = stylesheet.into();
+        self
+    }
+
+    /// Adds a [`Chapter`] after those already in the [`Book`].
+    ///
+    /// [`Chapter`]: Chapter
+    /// [`Book`]: Book
+    #[must_use]
+    pub fn with_chapter(mut self, chapter: Chapter) -> Self {
+        self.chapters.push(chapter);
+        self
+    }
+
+    /// Gets the chapters of the [`Book`] in reading order.
+    ///
+    /// [`Book`]: Book
+    #[inline]
+    #[must_use]
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    /// Writes the [`Book`] as an EPUB to the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be created or written.
+    ///
+    /// [`Book`]: Book
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = File::create(path).map_err(|_| Error::FileWriteError)?;
+        let mut zip = ZipWriter::new(file);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+        add(&mut zip, "mimetype", MIMETYPE, stored)?;
+        add(
+            &mut zip,
+            "META-INF/container.xml",
+            CONTAINER,
+            SimpleFileOptions::default(),
+        )?;
+
+        let files = [
+            ("content.opf".to_owned(), self.package()),
+            ("nav.xhtml".to_owned(), self.nav()),
+            ("styles.css".to_owned(), self.stylesheet.clone()),
+        ]
+        .into_iter()
+        .chain(self.chapters.iter().map(|chapter| {
+            (
+                chapter.href.clone(),
+                self.xhtml(&chapter.href, &chapter.title, &chapter.content),
+            )
+        }));
+
+        for (href, contents) in files {
+            add(
+                &mut zip,
+                &format!("{}/{}", CONTENT_DIR, href),
+                &contents,
+                SimpleFileOptions::default(),
+            )?;
+        }
+
+        zip.finish().map_err(|_| Error::FileWriteError)?;
+        Ok(())
+    }
+
+    /// Creates the package document, listing the book's metadata, files, and
+    /// reading order.
+    fn package(&self) -> String {
+        let modified = self
+            .modified
+            .to_offset(time::UtcOffset::UTC)
+            .replace_nanosecond(0)
+            .unwrap_or(self.modified)
+            .format(&Rfc3339)
+            .unwrap_or_default();
+
+        let (items, refs): (String, String) = self
+            .chapters
+            .iter()
+            .enumerate()
+            .map(|(i, chapter)| {
+                (
+                    format!(
+                        "    <item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+                        i + 1,
+                        href::encode(&chapter.href)
+                    ),
+                    format!("    <itemref idref=\"chapter-{}\"/>\n", i + 1),
+                )
+            })
+            .unzip();
+
+        let creator = match &self.author {
+            Some(author) => format!("    <dc:creator>{}</dc:creator>\n", escape::html(author)),
+            None => String::new(),
+        };
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"id\" xml:lang=\"{lang}\">\n\
+            \x20 <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+            \x20   <dc:identifier id=\"id\">{}</dc:identifier>\n\
+            \x20   <dc:title>{}</dc:title>\n\
+            \x20   <dc:language>{lang}</dc:language>\n\
+            {}\
+            \x20   <meta property=\"dcterms:modified\">{}</meta>\n\
+            \x20 </metadata>\n\
+            \x20 <manifest>\n\
+            \x20   <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n\
+            \x20   <item id=\"styles\" href=\"styles.css\" media-type=\"text/css\"/>\n\
+            {}\
+            \x20 </manifest>\n\
+            \x20 <spine>\n\
+            \x20   <itemref idref=\"nav\"/>\n\
+            {}\
+            \x20 </spine>\n\
+            </package>\n",
+            escape::html(&self.identifier),
+            escape::html(&self.title),
+            creator,
+            modified,
+            items,
+            refs,
+            lang = escape::html(&self.lang),
+        )
+    }
+
+    /// Creates the navigation document, the book's table of contents.
+    fn nav(&self) -> String {
+        let entries = self
+            .chapters
+            .iter()
+            .map(|chapter| {
+                format!(
+                    "<li><a href=\"{}\">{}</a></li>",
+                    href::encode(&chapter.href),
+                    escape::html(&chapter.title)
+                )
+            })
+            .collect::<String>();
+
+        self.xhtml(
+            "nav.xhtml",
+            &self.title,
+            &format!(
+                "<nav epub:type=\"toc\" id=\"toc\"><h1>{}</h1><ol>{}</ol></nav>",
+                escape::html(&self.title),
+                entries
+            ),
+        )
+    }
+
+    /// Wraps the given body in an XHTML document at the given href.
+    fn xhtml(&self, href: &str, title: &str, body: &str) -> String {
+        let stylesheet = format!("{}styles.css", "../".repeat(href.matches('/').count()));
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <!DOCTYPE html>\n\
+            <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"{lang}\" xml:lang=\"{lang}\">\n\
+            <head><meta charset=\"UTF-8\"/><title>{}</title><link rel=\"stylesheet\" href=\"{}\"/></head>\n\
+            <body>{}</body>\n\
+            </html>\n",
+            escape::html(title),
+            stylesheet,
+            body,
+            lang = escape::html(&self.lang),
+        )
+    }
+}
+
+/// Adds a file of the given name and contents to the archive.
+fn add(
+    zip: &mut ZipWriter<File>,
+    name: &str,
+    contents: &str,
+    options: SimpleFileOptions,
+) -> Result<()> {
+    zip.start_file(name, options)
+        .map_err(|_| Error::FileWriteError)?;
+    zip.write_all(contents.as_bytes())
+        .map_err(|_: io::Error| Error::FileWriteError)
+}
+
+/// The result type of EPUB related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while writing an EPUB.
+#[derive(Debug)]
+pub enum Error {
+    /// I/O failure to write the book.
+    FileWriteError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, io::Read};
+
+    #[test]
+    fn write_test() {
+        let path = env::temp_dir().join(format!("whim-epub-test-{}.epub", std::process::id()));
+
+        Book::new(
+            "Notes & Things",
+            "en",
+            "https://example.com",
+            time::OffsetDateTime::UNIX_EPOCH,
+        )
+        .with_author("Ada")
+        .with_chapter(Chapter {
+            href: "blog/my post.html".to_owned(),
+            title: "My <Post>".to_owned(),
+            content: "<p>Hello</p>".to_owned(),
+        })
+        .write(&path)
+        .unwrap();
+
+        let mut zip = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut read = |name: &str| {
+            let mut contents = String::new();
+            zip.by_name(name)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+
+        let package = read("OEBPS/content.opf");
+        assert!(package.contains("<dc:title>Notes &amp; Things</dc:title>"));
+        assert!(package.contains("href=\"blog/my%20post.html\""));
+        assert!(package.contains(">1970-01-01T00:00:00Z<"));
+        assert!(package.contains("<dc:creator>Ada</dc:creator>"));
+        assert!(read("OEBPS/nav.xhtml").contains(">My &lt;Post&gt;</a>"));
+        assert!(read("OEBPS/blog/my post.html").contains("href=\"../styles.css\""));
+
+        let mimetype = zip.by_index(0).unwrap();
+        assert_eq!(mimetype.name(), "mimetype");
+        assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Escaping of text written into generated HTML. Names of documents, authors,
+//! and series come from the documents themselves, so may hold characters HTML
+//! gives meaning to.
+
+/// Escapes the given text for use in HTML, either as content or within a
+/// quoted attribute. The same escaping suits the XHTML and XML of EPUBs.
+#[must_use]
+pub fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_test() {
+        assert_eq!(html("Plain"), "Plain");
+        assert_eq!(
+            html("<b>\"Fish\" & Chips</b>"),
+            "&lt;b&gt;&quot;Fish&quot; &amp; Chips&lt;/b&gt;"
+        );
+        assert_eq!(html("&amp;"), "&amp;amp;");
+    }
+}
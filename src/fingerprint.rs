@@ -0,0 +1,135 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Fingerprinting of stylesheets and scripts, enabled by setting
+//! `fingerprint` under `site`. Each is written with a hash of its content in
+//! its name, e.g. `styles.3f2a9c1b.css`, and pages link to it by that name,
+//! so hosts may cache them for as long as they like; a changed asset is given
+//! a new name rather than served stale.
+
+use crate::{hash::HashAlgorithm, href};
+use std::{collections::HashMap, path::Path};
+
+/// Extensions of the assets which are fingerprinted.
+const EXTENSIONS: [&str; 2] = ["css", "js"];
+
+/// Attributes of page elements which may link to an asset.
+const ATTRIBUTES: [&str; 2] = ["href=\"", "src=\""];
+
+/// Returns true if the asset at the given href is fingerprinted.
+#[must_use]
+pub fn applies(href: &str) -> bool {
+    Path::new(href)
+        .extension()
+        .is_some_and(|ext| EXTENSIONS.iter().any(|e| *e == ext))
+}
+
+/// Gets the fingerprinted href of the asset at the given href with the given
+/// content, the hash being placed before its extension.
+#[must_use]
+pub fn fingerprinted(href: &str, content: &[u8]) -> String {
+    let hash = HashAlgorithm::Xxh3.hash(content).to_string();
+    let hash = &hash[..8];
+
+    match href.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}.{}.{}", stem, hash, ext),
+        None => format!("{}.{}", href, hash),
+    }
+}
+
+/// Rewrites links in the given page, written to the given href, to assets
+/// which have been renamed, given as a map of each asset's href to its
+/// fingerprinted href. Links may be relative to the page, rooted, or begin
+/// with the given base URL.
+#[must_use]
+pub fn rewrite(
+    page: &str,
+    from: &str,
+    base_url: Option<&str>,
+    renamed: &HashMap<String, String>,
+) -> String {
+    let dir = Path::new(from).parent().unwrap_or(Path::new(""));
+    let mut rewritten = String::with_capacity(page.len());
+    let mut rest = page;
+
+    while let Some((start, attribute)) = ATTRIBUTES
+        .iter()
+        .filter_map(|a| Some((rest.find(a)?, a)))
+        .min_by_key(|(start, _)| *start)
+    {
+        let value_start = start + attribute.len();
+        let value_end = match rest[value_start..].find('"') {
+            Some(end) => value_start + end,
+            None => break,
+        };
+
+        let value = &rest[value_start..value_end];
+        let target = match (base_url.and_then(|b| value.strip_prefix(b)), value) {
+            (Some(rooted), _) => Some(rooted.trim_start_matches('/').to_owned()),
+            (None, v) if v.starts_with('/') => Some(v.trim_start_matches('/').to_owned()),
+            (None, v) if v.contains(':') || v.starts_with('#') => None,
+            (None, v) => Some(href::from_path(&dir.join(v))),
+        };
+
+        rewritten.push_str(&rest[..value_start]);
+
+        // Fingerprinting only renames files, so only the name in the link is
+        // replaced, leaving the rest of the link as it was.
+        match target.and_then(|t| renamed.get(&t)) {
+            Some(fingerprinted) => {
+                let name = fingerprinted.rsplit('/').next().unwrap_or(fingerprinted);
+                let prefix = value.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+                match prefix.is_empty() {
+                    true => rewritten.push_str(name),
+                    false => rewritten.push_str(&format!("{}/{}", prefix, name)),
+                }
+            }
+            None => rewritten.push_str(value),
+        }
+
+        rest = &rest[value_end..];
+    }
+
+    rewritten + rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrite_test() {
+        let styles = fingerprinted("styles.css", b"body {}");
+        let renamed = HashMap::from([
+            ("styles.css".to_owned(), styles.clone()),
+            ("fonts/x.js".to_owned(), "fonts/x.00000000.js".to_owned()),
+        ]);
+
+        assert!(applies("styles.css") && !applies("search-index.json"));
+        assert_eq!(styles, fingerprinted("styles.css", b"body {}"));
+        assert_ne!(styles, fingerprinted("styles.css", b"body { margin: 0 }"));
+        assert_eq!(
+            rewrite(
+                r#"<link href="../styles.css"><script src="../fonts/x.js"></script><a href="styles.css">"#,
+                "blog/post.html",
+                None,
+                &renamed
+            ),
+            format!(
+                r#"<link href="../{}"><script src="../fonts/x.00000000.js"></script><a href="styles.css">"#,
+                styles
+            )
+        );
+        assert_eq!(
+            rewrite(
+                r#"<link href="https://example.com/styles.css">"#,
+                "post.html",
+                Some("https://example.com"),
+                &renamed
+            ),
+            format!(r#"<link href="https://example.com/{}">"#, styles)
+        );
+    }
+}
@@ -6,29 +6,108 @@
 //! for its ease of implementation. Based off the contents of this
 //! [Wikipidia article](https://en.wikipedia.org/wiki/Fowler-Noll-Vo_hash_function)
 
+use std::hash::Hasher;
+
 const FNV_OFFSET_BASIS: u64 = 14695981039346656037;
 const FNV_PRIME: u64 = 1099511628211;
+const FNV_OFFSET_BASIS_128: u128 = 144066263297769815596495629667062367629;
+const FNV_PRIME_128: u128 = 309485009821345068724781371;
+
+/// An incremental FNV-1 hasher, bytes may be given a chunk at a time and the
+/// result is the same as hashing them all at once with [`hash`].
+///
+/// [`hash`]: hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1Hasher {
+    state: u64,
+}
+
+impl Fnv1Hasher {
+    /// Creates a new [`Fnv1Hasher`] which has not been given any bytes.
+    ///
+    /// [`Fnv1Hasher`]: Fnv1Hasher
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+
+    /// Feeds the given bytes into the hash.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        self.state = bytes.as_ref().iter().fold(self.state, |acc, &i| {
+            lower_byte_xor(acc.wrapping_mul(FNV_PRIME), i)
+        });
+    }
+}
+
+impl Default for Fnv1Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Fnv1Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
 
-pub trait Hashable {
-    /// Calculates the FNV-1 hash on `self`.
-    fn fnv1_hash(&self) -> u64;
+/// An incremental 128-bit FNV-1 hasher, otherwise the same as [`Fnv1Hasher`].
+/// A wider hash makes it much less likely that two different versions of a
+/// document have the same hash, which would hide a change.
+///
+/// [`Fnv1Hasher`]: Fnv1Hasher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1Hasher128 {
+    state: u128,
 }
 
-impl<T> Hashable for T
-where
-    T: AsRef<[u8]>,
-{
-    fn fnv1_hash(&self) -> u64 {
-        hash(self)
+impl Fnv1Hasher128 {
+    /// Creates a new [`Fnv1Hasher128`] which has not been given any bytes.
+    ///
+    /// [`Fnv1Hasher128`]: Fnv1Hasher128
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS_128,
+        }
+    }
+
+    /// Feeds the given bytes into the hash.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        // XOR with a `u8` only ever changes the lowest byte.
+        self.state = bytes.as_ref().iter().fold(self.state, |acc, &i| {
+            acc.wrapping_mul(FNV_PRIME_128) ^ u128::from(i)
+        });
+    }
+
+    /// Gets the hash of all bytes given so far.
+    #[inline]
+    #[must_use]
+    pub fn finish(&self) -> u128 {
+        self.state
+    }
+}
+
+impl Default for Fnv1Hasher128 {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// Performs an FNV-1 hash on the given bytes and returns the result.
 #[must_use]
 pub fn hash(bytes: impl AsRef<[u8]>) -> u64 {
-    bytes.as_ref().iter().fold(FNV_OFFSET_BASIS, |acc, &i| {
-        lower_byte_xor(acc.wrapping_mul(FNV_PRIME), i)
-    })
+    let mut hasher = Fnv1Hasher::new();
+    hasher.update(bytes);
+    hasher.finish()
 }
 
 /// Performs a XOR operation between the lowest byte of the [`u64`] and the
@@ -45,14 +124,36 @@ fn lower_byte_xor(a: u64, b: u8) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::Hashable;
+    use super::*;
 
     #[test]
     fn check_hash_differences() {
         let a: [u8; 6] = [32, 45, 234, 58, 72, 37];
         let b: [u8; 6] = [23, 43, 127, 32, 32, 123];
 
-        assert_ne!(a.fnv1_hash(), b.fnv1_hash());
-        assert_eq!(a.fnv1_hash(), a.clone().fnv1_hash());
+        assert_ne!(hash(a), hash(b));
+        assert_eq!(hash(a), hash(&a[..]));
+    }
+
+    #[test]
+    fn streaming_hash() {
+        let bytes: Vec<u8> = (0..20_000).map(|i| (i % 251) as u8).collect();
+        let mut hasher = Fnv1Hasher::new();
+
+        for chunk in bytes.chunks(7) {
+            hasher.write(chunk);
+        }
+
+        assert_eq!(hasher.finish(), hash(&bytes));
+    }
+
+    #[test]
+    fn hash_128() {
+        // Known FNV-1 128-bit hashes of "" and "a".
+        let mut hasher = Fnv1Hasher128::new();
+        assert_eq!(hasher.finish(), FNV_OFFSET_BASIS_128);
+
+        hasher.update("a");
+        assert_eq!(hasher.finish(), 0xd228cb69101a8caf78912b704e4a141e);
     }
 }
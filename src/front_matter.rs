@@ -0,0 +1,296 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Per-document settings, written as TOML between lines of `+++` at the very
+//! start of a document, e.g.
+//!
+//! ```markdown
+//! +++
+//! comments = false
+//! tags = ["rust", "notes"]
+//! layout = "project"
+//! +++
+//!
+//! # My Note
+//! ```
+//!
+//! or as YAML between lines of `---`, e.g.
+//!
+//! ```markdown
+//! ---
+//! comments: false
+//! tags: [rust, notes]
+//! ---
+//! ```
+//!
+//! Front matter is not part of a document's content, and unknown settings are
+//! ignored.
+
+use crate::diagnostic::Diagnostic;
+use serde::Deserialize;
+use std::{error, fmt, ops::Range, path::Path, result};
+
+/// The languages front matter may be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    /// Gets the line opening and closing front matter in this format.
+    #[inline]
+    #[must_use]
+    const fn delimiter(self) -> &'static str {
+        match self {
+            Format::Toml => "+++",
+            Format::Yaml => "---",
+        }
+    }
+
+    /// Gets the hint given when front matter in this format cannot be parsed.
+    #[inline]
+    #[must_use]
+    const fn hint(self) -> &'static str {
+        match self {
+            Format::Toml => "front matter is written in TOML between lines of `+++`",
+            Format::Yaml => "front matter is written in YAML between lines of `---`",
+        }
+    }
+}
+
+/// Settings of a single document, see the [module] documentation.
+///
+/// [module]: crate::front_matter
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct FrontMatter {
+    /// Whether to show the site's comments on the document, if it has any.
+    comments: Option<bool>,
+
+    /// Tags of the document, relating it to others with the same tags.
+    tags: Vec<String>,
+
+    /// Name of the series the document is part of, if any.
+    series: Option<String>,
+
+    /// Position of the document in its series, starting from one.
+    part: Option<u32>,
+
+    /// Author of the document, in place of the site's author.
+    author: Option<String>,
+
+    /// Name of the layout the document's page uses, in place of the default.
+    layout: Option<String>,
+
+    /// Passphrase the document's page is protected by, if any.
+    protect: Option<String>,
+
+    /// Whether to give footnotes as sidenotes, in place of the site's choice.
+    sidenotes: Option<bool>,
+}
+
+impl FrontMatter {
+    /// Parses the front matter of the given document source, read from the
+    /// given path, returning the default [`FrontMatter`] if it has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, logging where and why, if the front matter is not
+    /// valid TOML or YAML or has invalid settings.
+    ///
+    /// [`FrontMatter`]: FrontMatter
+    pub fn parse(path: impl AsRef<Path>, source: &str) -> Result<Self> {
+        let (format, range) = match locate(source) {
+            Some((format, range, _)) => (format, range),
+            None => return Ok(Self::default()),
+        };
+
+        let text = &source[range.clone()];
+
+        // Spans are found relative to the front matter, then offset into the
+        // source for the diagnostic.
+        let parsed = match format {
+            Format::Toml => toml::from_str(text).map_err(|e| {
+                let span = e.span().unwrap_or(0..text.len());
+                (span, e.message().to_owned())
+            }),
+            Format::Yaml if text.trim().is_empty() => Ok(Self::default()),
+            Format::Yaml => serde_yaml::from_str(text).map_err(|e| {
+                let start = e.location().map_or(0, |l| l.index());
+                (start..start, e.to_string())
+            }),
+        };
+
+        parsed.map_err(|(span, message)| {
+            let span = span.start + range.start..span.end + range.start;
+
+            log::error!(
+                "{}",
+                Diagnostic::from_span(path, source, span, message).with_hint(format.hint())
+            );
+
+            Error::DeserializationError
+        })
+    }
+
+    /// Gets whether comments should be shown on the document, [`None`] if the
+    /// document leaves it to the site.
+    ///
+    /// [`None`]: None
+    #[inline]
+    #[must_use]
+    pub fn comments(&self) -> Option<bool> {
+        self.comments
+    }
+
+    /// Gets the tags of the document.
+    #[inline]
+    #[must_use]
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Gets the name of the series the document is part of, if any.
+    #[inline]
+    #[must_use]
+    pub fn series(&self) -> Option<&str> {
+        self.series.as_deref()
+    }
+
+    /// Gets the position of the document in its series, if given.
+    #[inline]
+    #[must_use]
+    pub fn part(&self) -> Option<u32> {
+        self.part
+    }
+
+    /// Gets the author of the document, if given.
+    #[inline]
+    #[must_use]
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Gets the name of the layout of the document, if given.
+    #[inline]
+    #[must_use]
+    pub fn layout(&self) -> Option<&str> {
+        self.layout.as_deref()
+    }
+
+    /// Gets the passphrase protecting the document's page, if given, see the
+    /// [`protect`] module.
+    ///
+    /// [`protect`]: crate::protect
+    #[inline]
+    #[must_use]
+    pub fn protect(&self) -> Option<&str> {
+        self.protect.as_deref()
+    }
+
+    /// Gets whether footnotes should be given as sidenotes, [`None`] if the
+    /// document leaves it to the site.
+    ///
+    /// [`None`]: None
+    #[inline]
+    #[must_use]
+    pub fn sidenotes(&self) -> Option<bool> {
+        self.sidenotes
+    }
+}
+
+/// Gets the content of the given document source, without its front matter.
+#[must_use]
+pub fn strip(source: &str) -> &str {
+    match locate(source) {
+        Some((_, _, body)) => &source[body..],
+        None => source,
+    }
+}
+
+/// Finds the format and range of the front matter within the given source and
+/// the index the rest of the document starts at. Front matter must open on the
+/// first line and be closed with the same delimiter.
+fn locate(source: &str) -> Option<(Format, Range<usize>, usize)> {
+    let mut lines = source.split_inclusive('\n');
+    let open = lines.next()?;
+
+    let format = [Format::Toml, Format::Yaml]
+        .into_iter()
+        .find(|f| open.trim_end() == f.delimiter())?;
+
+    let mut end = open.len();
+
+    for line in lines {
+        if line.trim_end() == format.delimiter() {
+            return Some((format, open.len()..end, end + line.len()));
+        }
+
+        end += line.len();
+    }
+
+    None
+}
+
+/// The result type of front matter related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while parsing front matter.
+#[derive(Debug)]
+pub enum Error {
+    /// The front matter is not valid TOML or YAML or has invalid settings.
+    DeserializationError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_matter_test() {
+        let source = "+++\r\ncomments = false\r\ntags = [\"a\"]\r\n+++\r\n# Title\n";
+        let front_matter = FrontMatter::parse("a.md", source).unwrap();
+
+        assert_eq!(strip(source), "# Title\n");
+        assert_eq!(front_matter.comments(), Some(false));
+        assert_eq!(front_matter.tags(), ["a"]);
+
+        // Unclosed or late front matter is part of the content.
+        for source in ["+++\ncomments = false\n# Title", "# Title\n+++\n+++\n"] {
+            assert_eq!(strip(source), source);
+            assert_eq!(
+                FrontMatter::parse("a.md", source).unwrap(),
+                FrontMatter::default()
+            );
+        }
+
+        assert_eq!(strip("+++\n+++"), "");
+        assert!(FrontMatter::parse("a.md", "+++\ncomments = 1\n+++\n").is_err());
+
+        let source = "---\nprotect: secret\ntags: [a, b]\n---\n# Title\n";
+        let front_matter = FrontMatter::parse("a.md", source).unwrap();
+
+        assert_eq!(strip(source), "# Title\n");
+        assert_eq!(front_matter.protect(), Some("secret"));
+        assert_eq!(front_matter.tags(), ["a", "b"]);
+
+        // Delimiters must match.
+        assert_eq!(strip("---\ntags: [a]\n+++\n"), "---\ntags: [a]\n+++\n");
+        assert_eq!(strip("---\n---\n# Title"), "# Title");
+        assert_eq!(
+            FrontMatter::parse("a.md", "---\n---\n").unwrap(),
+            FrontMatter::default()
+        );
+        assert!(FrontMatter::parse("a.md", "---\ncomments: 1\n---\n").is_err());
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Asks git which files exist and which have changed, so that large
+//! repositories need not be walked and hashed in full. Files git ignores are
+//! never listed. Paths are relative to the directory git is run in.
+
+use std::{
+    error, fmt,
+    path::{Path, PathBuf},
+    process, result,
+};
+
+/// Lists every file in the given directory which is either tracked by git or
+/// untracked but not ignored.
+///
+/// # Errors
+///
+/// Returns an error if git could not be run or the directory is not in a git
+/// repository.
+pub fn files(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    git(
+        dir,
+        &[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ],
+    )
+}
+
+/// Gets the hash of the commit checked out in the given directory.
+///
+/// # Errors
+///
+/// Returns an error if git could not be run, the directory is not in a git
+/// repository, or the repository has no commits.
+pub fn head(dir: impl AsRef<Path>) -> Result<String> {
+    git(dir, &["rev-parse", "HEAD"])?
+        .first()
+        .map(|hash| hash.to_string_lossy().trim().to_owned())
+        .ok_or(Error::GitFailed)
+}
+
+/// Lists the files in the given directory which differ from the given commit,
+/// whether committed since, staged, or not, along with untracked files which
+/// are not ignored.
+///
+/// # Errors
+///
+/// Returns an error if git could not be run, the directory is not in a git
+/// repository, or the commit does not exist.
+pub fn changed(dir: impl AsRef<Path>, since: &str) -> Result<Vec<PathBuf>> {
+    let mut changed = git(&dir, &["diff", "-z", "--name-only", "--relative", since])?;
+    changed.extend(git(
+        &dir,
+        &["ls-files", "-z", "--others", "--exclude-standard"],
+    )?);
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+/// Runs git with the given arguments and splits its output into paths.
+fn git(dir: impl AsRef<Path>, args: &[&str]) -> Result<Vec<PathBuf>> {
+    log::debug!("running git {}", args.join(" "));
+
+    let output = process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stderr(process::Stdio::null())
+        .output()
+        .map_err(|_| Error::SpawnError)?;
+
+    if !output.status.success() {
+        return Err(Error::GitFailed);
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+/// The result type of git related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while asking git about files.
+#[derive(Debug)]
+pub enum Error {
+    /// git could not be started, it may not be installed.
+    SpawnError,
+
+    /// git exited unsuccessfully, e.g. outside of a repository.
+    GitFailed,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn git_test() {
+        let dir = std::env::temp_dir().join("whim-git-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("docs")).unwrap();
+
+        let run = |args: &[&str]| {
+            process::Command::new("git")
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+
+        // Nothing to test against without git.
+        if !run(&["init", "-q"]) {
+            return;
+        }
+
+        for file in ["a.md", "b.md", "ignored.md", "docs/c.md"] {
+            fs::write(dir.join(file), file).unwrap();
+        }
+
+        fs::write(dir.join(".gitignore"), "ignored.md\n").unwrap();
+        run(&["add", "a.md", "docs/c.md", ".gitignore"]);
+
+        assert!(matches!(changed(&dir, "HEAD"), Err(Error::GitFailed)));
+
+        run(&[
+            "-c",
+            "user.name=whim",
+            "-c",
+            "user.email=whim@example.com",
+            "commit",
+            "-qm",
+            "init",
+        ]);
+        fs::write(dir.join("docs/c.md"), "changed").unwrap();
+
+        let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        let mut all = files(&dir).unwrap();
+        all.sort();
+
+        assert_eq!(all, paths(&[".gitignore", "a.md", "b.md", "docs/c.md"]));
+        assert_eq!(
+            changed(&dir, "HEAD").unwrap(),
+            paths(&["b.md", "docs/c.md"])
+        );
+        assert_eq!(changed(dir.join("docs"), "HEAD").unwrap(), paths(&["c.md"]));
+
+        // Changes committed since a commit are changes all the same.
+        let init = head(&dir).unwrap();
+        fs::write(dir.join("a.md"), "changed").unwrap();
+        run(&[
+            "-c",
+            "user.name=whim",
+            "-c",
+            "user.email=whim@example.com",
+            "commit",
+            "-qam",
+            "edit",
+        ]);
+
+        assert_ne!(head(&dir).unwrap(), init);
+        assert_eq!(changed(&dir, "HEAD").unwrap(), paths(&["b.md"]));
+        assert_eq!(
+            changed(&dir, &init).unwrap(),
+            paths(&["a.md", "b.md", "docs/c.md"])
+        );
+        assert!(changed(&dir, "0000000").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
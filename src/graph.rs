@@ -0,0 +1,191 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! An optional page mapping how documents link to one another. Each document
+//! is a node and each link from one document to another an edge, the graph
+//! being embedded in the page as JSON and drawn by a small script.
+
+use crate::href;
+use build_html::{self as html, HtmlContainer};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
+
+/// The href of the generated graph page.
+pub const PAGE_HREF: &str = "graph.html";
+
+/// The href of the [`SCRIPT`], relative to the root of the output directory.
+///
+/// [`SCRIPT`]: SCRIPT
+pub const SCRIPT_HREF: &str = "graph.js";
+
+/// Script loaded by the graph page, it lays out the embedded graph and draws
+/// it with each node linking to its document.
+pub const SCRIPT: &str = include_str!("../theme/graph.js");
+
+/// A document drawn in the graph, see [`Graph::new`].
+///
+/// [`Graph::new`]: Graph::new
+#[derive(Clone, Debug, PartialEq)]
+pub struct Document<'a> {
+    /// Path of the document's source, relative to the library's directory.
+    pub path: &'a str,
+
+    /// Href of the document's page, relative to the root of the output
+    /// directory.
+    pub href: String,
+
+    /// Title of the document, shown by its node.
+    pub title: &'a str,
+
+    /// Destination of every link in the document.
+    pub links: Vec<String>,
+}
+
+/// The link graph of a library's documents.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Graph {
+    nodes: Vec<Node>,
+
+    /// Indices of the nodes each link is from and to.
+    edges: BTreeSet<(usize, usize)>,
+}
+
+/// A single document's node in the [`Graph`].
+///
+/// [`Graph`]: Graph
+#[derive(Clone, Debug, PartialEq, Serialize)]
+struct Node {
+    title: String,
+    href: String,
+}
+
+impl Graph {
+    /// Creates a new [`Graph`] of the given documents, each node linking to
+    /// the href given by `link` for its document's href. Links may be to
+    /// either a document's source or its page, and links to anything other
+    /// than another document are left out.
+    ///
+    /// [`Graph`]: Graph
+    #[must_use]
+    pub fn new(docs: &[Document], link: impl Fn(&str) -> String) -> Self {
+        let index = docs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, doc)| {
+                [
+                    (href::from_path(Path::new(doc.path)), i),
+                    (doc.href.clone(), i),
+                ]
+            })
+            .collect::<HashMap<_, _>>();
+
+        let edges = docs
+            .iter()
+            .enumerate()
+            .flat_map(|(from, doc)| {
+                doc.links
+                    .iter()
+                    .filter_map(|l| index.get(&resolve(doc, l)?))
+                    .filter(move |to| **to != from)
+                    .map(move |to| (from, *to))
+            })
+            .collect();
+
+        Self {
+            nodes: docs
+                .iter()
+                .map(|doc| Node {
+                    title: doc.title.to_owned(),
+                    href: link(&doc.href),
+                })
+                .collect(),
+            edges,
+        }
+    }
+
+    /// Serializes the [`Graph`] into JSON which may be embedded in a page.
+    ///
+    /// [`Graph`]: Graph
+    #[must_use]
+    pub fn json(&self) -> String {
+        // Serializing plain strings and numbers to JSON cannot fail, and an
+        // escaped '/' keeps titles from closing the script element.
+        serde_json::to_string(self)
+            .unwrap_or_default()
+            .replace("</", "<\\/")
+    }
+}
+
+/// Resolves the given link from a document to the path or href, relative to
+/// the library's directory or root of the output directory, it points to.
+/// Returns [`None`] for links to other sites, to absolute paths, or within
+/// the document itself.
+///
+/// [`None`]: None
+fn resolve(doc: &Document, link: &str) -> Option<String> {
+    let link = link.split(['#', '?']).next().unwrap_or_default();
+
+    match link.is_empty() || link.contains(':') || link.starts_with('/') {
+        true => None,
+        false => {
+            let from = match link.ends_with(".md") {
+                true => doc.path,
+                false => &doc.href,
+            };
+
+            let dir = Path::new(from).parent().unwrap_or(Path::new(""));
+            Some(href::from_path(&dir.join(link)))
+        }
+    }
+}
+
+/// Creates the content of the graph page, which must be written to the root
+/// of the output directory alongside its script.
+#[must_use]
+pub fn content(graph: &Graph) -> html::Container {
+    html::Container::new(html::ContainerType::Div)
+        .with_attributes(vec![("class", "graph")])
+        .with_header(1, "Graph")
+        .with_raw(r#"<svg id="graph" role="img" aria-label="Graph of linked documents"></svg>"#)
+        .with_raw(format!(
+            r#"<script type="application/json" id="graph-data">{}</script>"#,
+            graph.json()
+        ))
+        .with_raw(format!(r#"<script src="{}"></script>"#, SCRIPT_HREF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_test() {
+        let links = |links: &[&str]| links.iter().map(|l| l.to_string()).collect();
+        let docs = [
+            Document {
+                path: "a.md",
+                href: "a.html".to_owned(),
+                title: "A </script>",
+                links: links(&["blog/b.md", "a.html#top", "https://example.com"]),
+            },
+            Document {
+                path: "blog/b.md",
+                href: "blog/b.html".to_owned(),
+                title: "B",
+                links: links(&["../a.html?x", "../a.md", "c.html"]),
+            },
+        ];
+
+        let graph = Graph::new(&docs, |href| href.to_owned());
+
+        assert_eq!(graph.edges, BTreeSet::from([(0, 1), (1, 0)]));
+        assert_eq!(
+            graph.json(),
+            r#"{"nodes":[{"title":"A <\/script>","href":"a.html"},{"title":"B","href":"blog/b.html"}],"edges":[[0,1],[1,0]]}"#
+        );
+    }
+}
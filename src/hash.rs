@@ -0,0 +1,355 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Hashing of documents for change detection with a choice of algorithm. A
+//! library records which algorithm it uses, and each document records the
+//! algorithm its hash was made with so that hashes can be migrated when the
+//! library's algorithm changes.
+
+use crate::fnv1_hash::{self, Fnv1Hasher, Fnv1Hasher128};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    fs,
+    hash::Hasher as _,
+    io::{self, Read},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    thread,
+};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Size of the buffer used to read chunks at a time when hashing readers, large
+/// enough that most documents are read in a single call.
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Most threads used by [`hash_files`] regardless of available parallelism,
+/// past this point reading from disk is the bottleneck.
+///
+/// [`hash_files`]: HashAlgorithm::hash_files
+const MAX_HASH_THREADS: usize = 8;
+
+/// A hash algorithm for detecting changes to documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    /// FNV-1, simple and the original algorithm used by whim.
+    #[default]
+    Fnv1,
+
+    /// XXH3, much faster than FNV-1 on large files.
+    Xxh3,
+
+    /// BLAKE3, a cryptographic hash truncated to 64 bits.
+    Blake3,
+
+    /// 128-bit FNV-1, for very large libraries where a collision between 64-bit
+    /// hashes is a real risk.
+    Fnv1x128,
+
+    /// BLAKE3 truncated to 128 bits.
+    Blake3x128,
+}
+
+impl HashAlgorithm {
+    /// Creates a new [`Hasher`] using this algorithm.
+    ///
+    /// [`Hasher`]: Hasher
+    #[must_use]
+    pub fn hasher(self) -> Hasher {
+        match self {
+            HashAlgorithm::Fnv1 => Hasher::Fnv1(Fnv1Hasher::new()),
+            HashAlgorithm::Xxh3 => Hasher::Xxh3(Box::new(Xxh3::new())),
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new()), 8),
+            HashAlgorithm::Fnv1x128 => Hasher::Fnv1x128(Fnv1Hasher128::new()),
+            HashAlgorithm::Blake3x128 => Hasher::Blake3(Box::new(blake3::Hasher::new()), 16),
+        }
+    }
+
+    /// Hashes the given bytes using this algorithm.
+    #[must_use]
+    pub fn hash(self, bytes: impl AsRef<[u8]>) -> Digest {
+        match self {
+            HashAlgorithm::Fnv1 => Digest(fnv1_hash::hash(bytes).into()),
+            _ => {
+                let mut hasher = self.hasher();
+                hasher.update(bytes);
+                hasher.finish()
+            }
+        }
+    }
+
+    /// Hashes everything read from the given reader using this algorithm, a
+    /// chunk at a time so that large files are hashed without being held in
+    /// memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error encountered while reading.
+    pub fn hash_reader(self, reader: impl Read) -> io::Result<Digest> {
+        let mut hasher = self.hasher();
+        read_chunks(reader, |chunk| hasher.update(chunk))?;
+        Ok(hasher.finish())
+    }
+
+    /// Hashes the bytes of the file at the given path using this algorithm.
+    /// The file is hashed as is, so it need not be valid UTF-8 and may be any
+    /// asset, such as an image or PDF.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error encountered while opening or reading the file.
+    pub fn hash_file(self, path: impl AsRef<Path>) -> io::Result<Digest> {
+        self.hash_reader(fs::File::open(path)?)
+    }
+
+    /// Hashes each of the files at the given paths like [`hash_file`], across
+    /// several threads. Files which cannot be read are left out of the
+    /// returned map.
+    ///
+    /// [`hash_file`]: HashAlgorithm::hash_file
+    #[must_use]
+    pub fn hash_files<P>(self, paths: &[P]) -> HashMap<PathBuf, Digest>
+    where
+        P: AsRef<Path> + Sync,
+    {
+        let threads = thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(MAX_HASH_THREADS);
+        let chunk_size = paths.len().div_ceil(threads).max(1);
+        log::debug!(
+            "hashing {} files with {:?} on {} threads",
+            paths.len(),
+            self,
+            threads
+        );
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = paths
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .filter_map(|p| Some((p.as_ref().to_owned(), self.hash_file(p).ok()?)))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        })
+    }
+}
+
+/// An incremental hasher for any [`HashAlgorithm`].
+///
+/// [`HashAlgorithm`]: HashAlgorithm
+#[derive(Clone)]
+pub enum Hasher {
+    /// An FNV-1 hasher.
+    Fnv1(Fnv1Hasher),
+
+    /// An XXH3 hasher.
+    Xxh3(Box<Xxh3>),
+
+    /// A 128-bit FNV-1 hasher.
+    Fnv1x128(Fnv1Hasher128),
+
+    /// A BLAKE3 hasher and the number of bytes its hash is truncated to.
+    Blake3(Box<blake3::Hasher>, usize),
+}
+
+impl Hasher {
+    /// Feeds the given bytes into the hash.
+    pub fn update(&mut self, bytes: impl AsRef<[u8]>) {
+        match self {
+            Hasher::Fnv1(h) => h.update(bytes),
+            Hasher::Xxh3(h) => h.update(bytes.as_ref()),
+            Hasher::Fnv1x128(h) => h.update(bytes),
+            Hasher::Blake3(h, _) => {
+                h.update(bytes.as_ref());
+            }
+        }
+    }
+
+    /// Gets the hash of all bytes given so far.
+    #[must_use]
+    pub fn finish(&self) -> Digest {
+        Digest(match self {
+            Hasher::Fnv1(h) => h.finish().into(),
+            Hasher::Xxh3(h) => h.digest().into(),
+            Hasher::Fnv1x128(h) => h.finish(),
+            Hasher::Blake3(h, len) => {
+                let mut first = [0; 16];
+                first[..*len].copy_from_slice(&h.finalize().as_bytes()[..*len]);
+                u128::from_le_bytes(first)
+            }
+        })
+    }
+}
+
+/// The result of hashing with any [`HashAlgorithm`], hashes narrower than 128
+/// bits occupy the lower bits. Displayed and serialized as hexadecimal, though
+/// integers are also accepted when deserializing since older libraries stored
+/// hashes that way.
+///
+/// [`HashAlgorithm`]: HashAlgorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Digest(pub u128);
+
+impl Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Pad to the width of a 64 or 128-bit hash, whichever this fits in.
+        match self.0 > u64::MAX.into() {
+            true => write!(f, "{:032x}", self.0),
+            false => write!(f, "{:016x}", self.0),
+        }
+    }
+}
+
+impl Serialize for Digest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DigestVisitor)
+    }
+}
+
+/// Visitor for deserializing a [`Digest`] from either a hexadecimal string or
+/// an integer.
+///
+/// [`Digest`]: Digest
+struct DigestVisitor;
+
+impl<'de> de::Visitor<'de> for DigestVisitor {
+    type Value = Digest;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a hexadecimal string or an unsigned integer")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Digest, E> {
+        u128::from_str_radix(v, 16)
+            .map(Digest)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Digest, E> {
+        Ok(Digest(v.into()))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Digest, E> {
+        Ok(Digest(v))
+    }
+}
+
+/// Reads the given reader to its end, passing each chunk read to the given
+/// function.
+///
+/// # Errors
+///
+/// Returns any error encountered while reading.
+pub fn read_chunks(mut reader: impl Read, mut f: impl FnMut(&[u8])) -> io::Result<()> {
+    let mut buffer = [0; READ_BUFFER_SIZE];
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(n) => f(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn algorithms_test() {
+        let bytes: Vec<u8> = (0..20_000).map(|i| (i % 251) as u8).collect();
+
+        // Known FNV-1 64-bit hash of "a".
+        assert_eq!(HashAlgorithm::Fnv1.hash("a").0, 0xaf63bd4c8601b7be);
+        assert_eq!(
+            HashAlgorithm::Blake3.hash(&bytes).0,
+            HashAlgorithm::Blake3x128.hash(&bytes).0 & u128::from(u64::MAX)
+        );
+
+        for algorithm in [
+            HashAlgorithm::Fnv1,
+            HashAlgorithm::Xxh3,
+            HashAlgorithm::Blake3,
+            HashAlgorithm::Fnv1x128,
+            HashAlgorithm::Blake3x128,
+        ] {
+            assert_eq!(
+                algorithm.hash_reader(bytes.as_slice()).unwrap(),
+                algorithm.hash(&bytes)
+            );
+            assert_ne!(algorithm.hash(&bytes), algorithm.hash(&bytes[1..]));
+        }
+    }
+
+    #[test]
+    fn borrowed_test() {
+        let string = String::from("some markdown");
+        let bytes: &[u8] = string.as_bytes();
+        let mut hasher = HashAlgorithm::Fnv1x128.hasher();
+        hasher.update(&bytes[..4]);
+        hasher.update(&string[4..]);
+
+        assert_eq!(hasher.finish(), HashAlgorithm::Fnv1x128.hash(&string));
+        assert_eq!(
+            HashAlgorithm::Fnv1.hash(string.as_str()),
+            HashAlgorithm::Fnv1.hash(bytes)
+        );
+        assert_eq!(
+            HashAlgorithm::Xxh3.hash(&bytes[..4]),
+            HashAlgorithm::Xxh3.hash("some")
+        );
+    }
+
+    #[test]
+    fn hash_files_test() {
+        let dir = std::env::temp_dir().join("whim-hash-files-test");
+        fs::create_dir_all(&dir).unwrap();
+
+        let paths: Vec<PathBuf> = (0..20).map(|i| dir.join(format!("{}.md", i))).collect();
+
+        for (i, path) in paths.iter().enumerate() {
+            fs::write(path, i.to_string()).unwrap();
+        }
+
+        let missing = dir.join("missing.md");
+        let hashes =
+            HashAlgorithm::Xxh3.hash_files(&[&paths[..], std::slice::from_ref(&missing)].concat());
+
+        assert_eq!(hashes.len(), paths.len());
+        assert!(!hashes.contains_key(&missing));
+        assert_eq!(hashes[&paths[7]], HashAlgorithm::Xxh3.hash("7"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn digest_test() {
+        let digest = Digest(0xa1b2c3);
+
+        assert_eq!(digest.to_string(), "0000000000a1b2c3");
+        assert_eq!(ron::to_string(&digest).unwrap(), "\"0000000000a1b2c3\"");
+        assert_eq!(ron::from_str::<Digest>("\"a1b2c3\"").unwrap(), digest);
+        assert_eq!(ron::from_str::<Digest>("10597059").unwrap(), digest);
+        assert!(ron::from_str::<Digest>("\"xyz\"").is_err());
+    }
+}
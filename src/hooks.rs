@@ -0,0 +1,164 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Shell commands configured to run before and after `whim build`. Each is run
+//! in the library's directory with the stage, output directory, and changed
+//! documents given in environment variables, so that other tools may be
+//! chained onto a build.
+
+use serde::{Deserialize, Serialize};
+use std::{error, fmt, path::Path, process, result};
+
+/// Environment variable holding the name of the stage a hook is run for.
+const STAGE_VAR: &str = "WHIM_HOOK";
+
+/// Environment variable holding the output directory of the build.
+const OUTPUT_VAR: &str = "WHIM_OUTPUT_DIR";
+
+/// Environment variable holding the paths of documents which have changed
+/// since they were last updated, one per line.
+const CHANGED_VAR: &str = "WHIM_CHANGED_FILES";
+
+/// The point of a build at which hooks are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Before the site is generated, a failing hook stops the build.
+    PreBuild,
+
+    /// After the site has been written successfully.
+    PostBuild,
+}
+
+impl Stage {
+    /// Gets the name of this [`Stage`] as given to hooks.
+    ///
+    /// [`Stage`]: Stage
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Stage::PreBuild => "pre_build",
+            Stage::PostBuild => "post_build",
+        }
+    }
+}
+
+/// Commands to run at each [`Stage`] of a build, in order.
+///
+/// [`Stage`]: Stage
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pre_build: Vec<String>,
+    post_build: Vec<String>,
+}
+
+impl Hooks {
+    /// Gets the commands run at the given [`Stage`].
+    ///
+    /// [`Stage`]: Stage
+    #[inline]
+    #[must_use]
+    pub fn commands(&self, stage: Stage) -> &[String] {
+        match stage {
+            Stage::PreBuild => &self.pre_build,
+            Stage::PostBuild => &self.post_build,
+        }
+    }
+
+    /// Runs each command of the given [`Stage`] in turn with the system shell,
+    /// stopping at the first to fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a command could not be run or exited unsuccessfully.
+    ///
+    /// [`Stage`]: Stage
+    pub fn run(&self, stage: Stage, output: impl AsRef<Path>, changed: &[&str]) -> Result<()> {
+        for hook in self.commands(stage) {
+            log::info!("running {} hook '{}'", stage.name(), hook);
+
+            let status = shell(hook)
+                .env(STAGE_VAR, stage.name())
+                .env(OUTPUT_VAR, output.as_ref())
+                .env(CHANGED_VAR, changed.join("\n"))
+                .status()
+                .map_err(|_| Error::SpawnError)?;
+
+            if !status.success() {
+                log::error!("{} hook '{}' failed with {}", stage.name(), hook, status);
+                return Err(Error::HookFailed);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a [`Command`] running the given command line with the system shell.
+///
+/// [`Command`]: process::Command
+fn shell(command: &str) -> process::Command {
+    let (program, flag) = match cfg!(windows) {
+        true => ("cmd", "/C"),
+        false => ("sh", "-c"),
+    };
+
+    let mut shell = process::Command::new(program);
+    shell.arg(flag).arg(command);
+    shell
+}
+
+/// The result type of hook related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while running hooks.
+#[derive(Debug)]
+pub enum Error {
+    /// The system shell could not be started.
+    SpawnError,
+
+    /// A hook exited unsuccessfully.
+    HookFailed,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    #[cfg(unix)]
+    fn run_test() {
+        let out = std::env::temp_dir().join("whim-hooks-test.txt");
+        let hooks = Hooks {
+            pre_build: vec![format!(
+                "printf '%s %s\\n%s' \"$WHIM_HOOK\" \"$WHIM_OUTPUT_DIR\" \"$WHIM_CHANGED_FILES\" > '{}'",
+                out.display()
+            )],
+            post_build: vec!["exit 1".to_owned(), "exit 0".to_owned()],
+        };
+
+        hooks
+            .run(Stage::PreBuild, "site", &["a.md", "b.md"])
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&out).unwrap(),
+            "pre_build site\na.md\nb.md"
+        );
+        assert!(matches!(
+            hooks.run(Stage::PostBuild, "site", &[]),
+            Err(Error::HookFailed)
+        ));
+
+        fs::remove_file(&out).unwrap();
+    }
+}
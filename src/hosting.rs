@@ -0,0 +1,164 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Configuration for static hosts, written alongside the site so that it
+//! deploys without further setup. Redirects are made from configured aliases
+//! and assets are given cache headers, fingerprinted assets being cached for
+//! good. Netlify and Cloudflare Pages read
+//! `_redirects` and `_headers` files, Vercel reads `vercel.json`.
+
+use crate::{search, theme};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Cache-Control header given to the theme and search assets, which may
+/// change whenever the site is built.
+const ASSET_CACHE: &str = "public, max-age=3600, must-revalidate";
+
+/// Cache-Control header given to fingerprinted assets, which are given new
+/// hrefs rather than changed, see the [`fingerprint`] module.
+///
+/// [`fingerprint`]: crate::fingerprint
+const FINGERPRINTED_CACHE: &str = "public, max-age=31536000, immutable";
+
+/// A static host to write configuration for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Host {
+    Netlify,
+    Vercel,
+    Cloudflare,
+}
+
+/// Creates the configuration files for the given [`Host`], as tuples of the
+/// href to write each to and its contents, making a permanent redirect for
+/// each of the given tuples of the href redirected from and the href or URL
+/// redirected to. Assets are given cache headers by the hrefs they are
+/// written to, given as a map of the hrefs of those fingerprinted to their
+/// fingerprinted hrefs.
+///
+/// [`Host`]: Host
+#[must_use]
+pub fn outputs(
+    host: Host,
+    redirects: &[(String, String)],
+    fingerprints: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let redirects = redirects
+        .iter()
+        .map(|(from, to)| (rooted(from), rooted(to)))
+        .collect::<Vec<_>>();
+
+    let mut fingerprinted = fingerprints
+        .values()
+        .map(|href| (rooted(href), FINGERPRINTED_CACHE))
+        .collect::<Vec<_>>();
+    fingerprinted.sort();
+
+    let assets = [
+        theme::STYLESHEET_HREF,
+        theme::TOGGLE_SCRIPT_HREF,
+        search::SCRIPT_HREF,
+        search::INDEX_HREF,
+    ]
+    .into_iter()
+    .filter(|href| !fingerprints.contains_key(*href))
+    .map(|href| (rooted(href), ASSET_CACHE))
+    .chain(fingerprinted)
+    .collect::<Vec<_>>();
+
+    match host {
+        Host::Netlify | Host::Cloudflare => vec![
+            (
+                "_redirects".to_owned(),
+                redirects
+                    .iter()
+                    .map(|(from, to)| format!("{} {} 301\n", from, to))
+                    .collect(),
+            ),
+            (
+                "_headers".to_owned(),
+                assets
+                    .iter()
+                    .map(|(href, cache)| format!("{}\n  Cache-Control: {}\n", href, cache))
+                    .collect(),
+            ),
+        ],
+        Host::Vercel => {
+            let config = json!({
+                "redirects": redirects
+                    .iter()
+                    .map(|(from, to)| json!({
+                        "source": from,
+                        "destination": to,
+                        "permanent": true,
+                    }))
+                    .collect::<Vec<_>>(),
+                "headers": assets
+                    .iter()
+                    .map(|(href, cache)| json!({
+                        "source": href,
+                        "headers": [{ "key": "Cache-Control", "value": cache }],
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+
+            // Serializing a `Value` cannot fail.
+            vec![(
+                "vercel.json".to_owned(),
+                serde_json::to_string_pretty(&config).unwrap_or_default(),
+            )]
+        }
+    }
+}
+
+/// Makes the given href relative to the root of the host, leaving URLs as
+/// they are.
+fn rooted(href: &str) -> String {
+    match href.contains("://") {
+        true => href.to_owned(),
+        false => format!("/{}", href.trim_start_matches('/')),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outputs_test() {
+        let redirects = [
+            ("old/post.html".to_owned(), "blog/post.html".to_owned()),
+            (
+                "/feed".to_owned(),
+                "https://example.com/feed.xml".to_owned(),
+            ),
+        ];
+
+        let netlify = outputs(Host::Netlify, &redirects, &HashMap::new());
+        assert_eq!(netlify[0].0, "_redirects");
+        assert_eq!(
+            netlify[0].1,
+            "/old/post.html /blog/post.html 301\n/feed https://example.com/feed.xml 301\n"
+        );
+        assert_eq!(netlify[1].0, "_headers");
+        assert!(netlify[1]
+            .1
+            .starts_with("/styles.css\n  Cache-Control: public"));
+
+        let fingerprints =
+            HashMap::from([("styles.css".to_owned(), "styles.0123abcd.css".to_owned())]);
+        let vercel = outputs(Host::Vercel, &redirects, &fingerprints);
+        let config: serde_json::Value = serde_json::from_str(&vercel[0].1).unwrap();
+        assert_eq!(vercel[0].0, "vercel.json");
+        assert_eq!(config["redirects"][0]["source"], "/old/post.html");
+        assert_eq!(config["redirects"][1]["permanent"], true);
+        assert_eq!(config["headers"][0]["source"], "/theme.js");
+        assert_eq!(config["headers"][3]["source"], "/styles.0123abcd.css");
+        assert_eq!(
+            config["headers"][3]["headers"][0]["value"],
+            FINGERPRINTED_CACHE
+        );
+    }
+}
@@ -2,32 +2,516 @@
 // Licensed under the MIT License.
 // See LICENSE file in repository root for full text.
 
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
 pub trait Href {
-    /// Returns the number of items in the given [`Href`].
-    ///
-    /// "/index.html" -> 1
-    /// "/blog/" -> 2
-    /// "../x" -> 2
+    /// Returns an href to `self` from the page at `from`, where both are
+    /// relative to the same root, e.g. "blog/b.html" from "notes/a.html" is
+    /// "../blog/b.html". An empty href is the root itself. Any query or
+    /// fragment of `self` is kept.
+    fn relative_to(&self, from: &Self) -> String;
+}
+
+/// Hrefs are parsed into [`Parts`] and only their paths are made relative,
+/// the query and fragment are kept as they are.
+///
+/// [`Parts`]: Parts
+impl Href for str {
+    fn relative_to(&self, from: &Self) -> String {
+        let parts = Parts::parse(self);
+
+        // An href of only a query or fragment is already relative to the page.
+        if parts.path.is_empty() && (parts.query.is_some() || parts.fragment.is_some()) {
+            return self.to_owned();
+        }
+
+        let from = Parts::parse(from);
+        parts.with_path(&Path::new(parts.path).relative_to(Path::new(from.path)))
+    }
+}
+
+/// [`Path`]s are normalized first, so "a/./b/../c.html" is treated as
+/// "a/c.html".
+///
+/// [`Path`]: Path
+impl Href for Path {
+    fn relative_to(&self, from: &Self) -> String {
+        let target = from_path(self);
+        let from = from_path(from);
+
+        let target_items: Vec<&str> = target.split('/').collect();
+        let from_items: Vec<&str> = from.split('/').collect();
+        let target_dirs = &target_items[..target_items.len() - 1];
+        let from_dirs = &from_items[..from_items.len() - 1];
+
+        let common = target_dirs
+            .iter()
+            .zip(from_dirs)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        "../".repeat(from_dirs.len() - common) + target_items[common..].join("/").as_str()
+    }
+}
+
+/// An href split into its path, query, and fragment, e.g. "a.html?q=1#top" has
+/// the path "a.html", the query "q=1", and the fragment "top".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parts<'a> {
+    /// Everything before the query or fragment, may be empty.
+    pub path: &'a str,
+
+    /// Everything after the '?' beginning the query, if there is one.
+    pub query: Option<&'a str>,
+
+    /// Everything after the '#' beginning the fragment, if there is one.
+    pub fragment: Option<&'a str>,
+}
+
+impl<'a> Parts<'a> {
+    /// Splits the given href into its [`Parts`]. The fragment begins at the
+    /// first '#' and the query at the first '?' before it.
     ///
-    /// [`Href`]: Href
-    fn path_items(&self) -> usize;
+    /// [`Parts`]: Parts
+    #[must_use]
+    pub fn parse(href: &'a str) -> Self {
+        let (rest, fragment) = match href.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (href, None),
+        };
+
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+
+        Self {
+            path,
+            query,
+            fragment,
+        }
+    }
 
-    /// Returns the number of parent accessors, or ".." path items, in the given
-    /// [`Href`].
+    /// Creates an href with the given path and the query and fragment of these
+    /// [`Parts`].
     ///
-    /// [`Href`]: Href
-    fn parent_accessors(&self) -> usize;
+    /// [`Parts`]: Parts
+    #[must_use]
+    pub fn with_path(&self, path: &str) -> String {
+        let mut href = path.to_owned();
+
+        if let Some(query) = self.query {
+            href.push('?');
+            href.push_str(query);
+        }
+
+        if let Some(fragment) = self.fragment {
+            href.push('#');
+            href.push_str(fragment);
+        }
+
+        href
+    }
 }
 
-impl Href for str {
-    fn path_items(&self) -> usize {
-        match self.chars().next().unwrap_or_default() == '/' {
-            true => self.matches('/').count(),
-            false => self.matches('/').count() + 1,
+/// Normalizes the given [`Path`] by its components without accessing the file
+/// system, removing "." items and resolving ".." items against the item before
+/// them where there is one. Leading ".." items are kept, unless the path is
+/// absolute since nothing is above the root. A '\\' separates items just like
+/// a '/', regardless of platform.
+///
+/// [`Path`]: Path
+#[must_use]
+pub fn normalize(path: &Path) -> PathBuf {
+    let path = match path.to_str() {
+        Some(s) if s.contains('\\') => Cow::Owned(PathBuf::from(normalize_separators(s).as_ref())),
+        _ => Cow::Borrowed(path),
+    };
+
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => (),
+                _ => normalized.push(Component::ParentDir),
+            },
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Creates an href from the given [`Path`], normalized and with its items
+/// separated by '/'.
+///
+/// [`Path`]: Path
+#[must_use]
+pub fn from_path(path: &Path) -> String {
+    let normalized = normalize(path);
+    let items: Vec<_> = normalized
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy()),
+            Component::ParentDir => Some("..".into()),
+            _ => None,
+        })
+        .collect();
+
+    match normalized.has_root() {
+        true => format!("/{}", items.join("/")),
+        false => items.join("/"),
+    }
+}
+
+/// Replaces each '\\' in the given href with a '/'. Paths on Windows may use
+/// either as a separator, but browsers take a '\\' as part of an item's name.
+#[must_use]
+pub fn normalize_separators(href: &str) -> Cow<'_, str> {
+    match href.contains('\\') {
+        true => Cow::Owned(href.replace('\\', "/")),
+        false => Cow::Borrowed(href),
+    }
+}
+
+/// Percent-encodes the given href for use in a link, every byte other than
+/// ASCII letters, digits, '-', '.', '_', '~', and the '/' separating items is
+/// encoded. Only links are encoded, the files they point to keep their names.
+#[must_use]
+pub fn encode(href: &str) -> String {
+    href.bytes()
+        .fold(String::with_capacity(href.len()), |mut acc, b| {
+            match b.is_ascii_alphanumeric() || b"-._~/".contains(&b) {
+                true => acc.push(b as char),
+                false => acc.push_str(&format!("%{:02X}", b)),
+            }
+
+            acc
+        })
+}
+
+/// Joins the given href onto a base URL, e.g. "blog/a.html" onto
+/// "https://example.com/notes/" is "https://example.com/notes/blog/a.html".
+/// The base may or may not end with a '/' and a leading '/' on the href is
+/// taken as the root of the site rather than of the host, so sites deployed to
+/// a sub-path keep it. Hrefs which are already absolute URLs are returned as
+/// is, and an empty base joins to just "/" and the href.
+#[must_use]
+pub fn join_base(base_url: &str, href: &str) -> String {
+    if href.starts_with("//") || href.split_once("://").is_some_and(|(s, _)| is_scheme(s)) {
+        return href.to_owned();
+    }
+
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        href.trim_start_matches('/')
+    )
+}
+
+/// Returns true if the given text is a valid URL scheme, e.g. "https".
+fn is_scheme(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+/// Finds hrefs which differ only by case, e.g. "About.html" and "about.html",
+/// returning each group of such hrefs sorted. Only one of each group survives
+/// being written to a case-insensitive file system, as on Windows and macOS by
+/// default, or being deployed to a host which ignores case.
+#[must_use]
+pub fn case_collisions<'a>(hrefs: impl IntoIterator<Item = &'a str>) -> Vec<Vec<&'a str>> {
+    let mut groups: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+
+    for href in hrefs {
+        groups.entry(href.to_lowercase()).or_default().push(href);
+    }
+
+    groups
+        .into_values()
+        .filter_map(|mut group| {
+            group.sort_unstable();
+            group.dedup();
+
+            match group.len() > 1 {
+                true => Some(group),
+                false => None,
+            }
+        })
+        .collect()
+}
+
+/// Finds hrefs given by more than one source, e.g. "My Note.md" and
+/// "my-note.md" both slugified to "my-note.html", returning each such href
+/// with its sources in the order given. Only one page survives being written
+/// to each.
+#[must_use]
+pub fn collisions<'a>(
+    hrefs: impl IntoIterator<Item = (String, &'a str)>,
+) -> Vec<(String, Vec<&'a str>)> {
+    let mut sources: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+
+    for (href, source) in hrefs {
+        sources.entry(href).or_default().push(source);
+    }
+
+    sources
+        .into_iter()
+        .filter(|(_, sources)| sources.len() > 1)
+        .collect()
+}
+
+/// Creates a slug from the given text for use in URLs, e.g. "My Note (v2)"
+/// becomes "my-note-v2". Text is lowercased, common accented Latin letters are
+/// transliterated to ASCII, runs of whitespace, dashes, and underscores become
+/// a single dash, and any other punctuation is removed.
+#[must_use]
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match transliterate(c) {
+            Some(s) => slug.push_str(s),
+            None if c.is_alphanumeric() => slug.push(c),
+            // Separators are only kept between other characters.
+            None if (c.is_whitespace() || c == '-' || c == '_')
+                && !slug.is_empty()
+                && !slug.ends_with('-') =>
+            {
+                slug.push('-')
+            }
+            None => (),
         }
     }
 
-    fn parent_accessors(&self) -> usize {
-        self.matches("..").count()
+    slug.trim_end_matches('-').to_owned()
+}
+
+/// Creates a slug from the given name like [`slugify`] for use as the stem of
+/// a page's href. Names with nothing a slug would keep, e.g. "++", are given
+/// as the hexadecimal of their bytes instead, "2b2b", so that no page is named
+/// by its extension alone.
+///
+/// [`slugify`]: slugify
+#[must_use]
+pub fn slugify_name(name: &str) -> String {
+    match slugify(name) {
+        slug if slug.is_empty() => name.bytes().map(|b| format!("{:02x}", b)).collect(),
+        slug => slug,
+    }
+}
+
+/// Slugifies each item of the given href with [`slugify_name`], keeping the
+/// extension of the last item, e.g. "Notes/My Note.html" becomes
+/// "notes/my-note.html".
+///
+/// [`slugify_name`]: slugify_name
+#[must_use]
+pub fn slugify_href(href: &str) -> String {
+    normalize_separators(href)
+        .split('/')
+        .map(|item| match item {
+            "" | "." | ".." => item.to_owned(),
+            _ => match item.rsplit_once('.') {
+                Some((stem, ext)) if !stem.is_empty() => {
+                    format!("{}.{}", slugify_name(stem), ext)
+                }
+                _ => slugify_name(item),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Gets the ASCII transliteration of common, lowercase, accented Latin
+/// letters.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ð' | 'ď' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => "i",
+        'ł' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ß' => "ss",
+        'ś' | 'š' => "s",
+        'ť' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_test() {
+        assert_eq!(
+            from_path(Path::new("./blog/./x/../post.md")),
+            "blog/post.md"
+        );
+        assert_eq!(from_path(Path::new("../a/b/..")), "../a");
+        assert_eq!(from_path(Path::new("/a/./b")), "/a/b");
+    }
+
+    #[test]
+    fn parts_test() {
+        assert_eq!(
+            Parts::parse("a/b.html?q=..#x/../y"),
+            Parts {
+                path: "a/b.html",
+                query: Some("q=.."),
+                fragment: Some("x/../y"),
+            }
+        );
+        assert_eq!(
+            Parts::parse("#top?x"),
+            Parts {
+                path: "",
+                query: None,
+                fragment: Some("top?x"),
+            }
+        );
+
+        assert_eq!("en/a.html#x".relative_to("en/b.html?q"), "a.html#x");
+        assert_eq!("a.html?q=1".relative_to("en/b.html"), "../a.html?q=1");
+        assert_eq!("#top".relative_to("en/b.html"), "#top");
+    }
+
+    #[test]
+    fn relative_to_test() {
+        assert_eq!("index.html".relative_to("index.html"), "index.html");
+        assert_eq!(
+            "index.html".relative_to("en/blog/a.html"),
+            "../../index.html"
+        );
+        assert_eq!("en/b.html".relative_to("en/blog/a.html"), "../b.html");
+        assert_eq!("en/blog/b.html".relative_to("en/blog/a.html"), "b.html");
+        assert_eq!(
+            "de/blog/a.html".relative_to("en/blog/a.html"),
+            "../../de/blog/a.html"
+        );
+        assert_eq!(
+            "blog/a.html".relative_to("./x/../index.html"),
+            "blog/a.html"
+        );
+        assert_eq!("".relative_to("en/a.html"), "../");
+    }
+
+    #[test]
+    fn separators_test() {
+        assert_eq!(normalize_separators("a\\b/c"), "a/b/c");
+        assert_eq!(
+            from_path(Path::new(".\\blog\\x/..\\post.md")),
+            "blog/post.md"
+        );
+        assert_eq!("en\\b.html".relative_to("en/blog\\a.html"), "../b.html");
+        assert_eq!(slugify_href("Notes\\My Note.html"), "notes/my-note.html");
+    }
+
+    #[test]
+    fn case_collisions_test() {
+        let hrefs = [
+            "about.html",
+            "en/About.html",
+            "en/about.html",
+            "en/ABOUT.html",
+            "index.html",
+            "index.html",
+            "Blog/a.html",
+            "blog/b.html",
+        ];
+
+        assert_eq!(
+            case_collisions(hrefs),
+            vec![vec!["en/ABOUT.html", "en/About.html", "en/about.html"]]
+        );
+    }
+
+    #[test]
+    fn join_base_test() {
+        let url = "https://example.com/notes/blog/a.html";
+
+        assert_eq!(join_base("https://example.com/notes/", "blog/a.html"), url);
+        assert_eq!(join_base("https://example.com/notes", "blog/a.html"), url);
+        assert_eq!(join_base("https://example.com/notes/", "/blog/a.html"), url);
+        assert_eq!(join_base("https://example.com/notes/", url), url);
+        assert_eq!(
+            join_base("https://example.com/", "//cdn.example.com/a.css"),
+            "//cdn.example.com/a.css"
+        );
+        assert_eq!(
+            join_base("https://example.com/notes/", ""),
+            "https://example.com/notes/"
+        );
+        assert_eq!(join_base("", "blog/a.html"), "/blog/a.html");
+        assert_eq!(join_base("", "a://b.html"), "a://b.html");
+        assert_eq!(join_base("", "a b://c.html"), "/a b://c.html");
+    }
+
+    #[test]
+    fn slugify_test() {
+        assert_eq!(slugify("My Note (v2)"), "my-note-v2");
+        assert_eq!(
+            slugify("  Crème Brûlée -- Straße_2 "),
+            "creme-brulee-strasse-2"
+        );
+        assert_eq!(slugify("Über 日本"), "uber-日本");
+        assert_eq!(slugify("?!"), "");
+        assert_eq!(
+            slugify_href("Notes/My Note (v2).html"),
+            "notes/my-note-v2.html"
+        );
+        assert_eq!(slugify_href("../.hidden"), "../hidden");
+        assert_eq!(slugify_name("C++"), "c");
+        assert_eq!(slugify_name("++"), "2b2b");
+        assert_eq!(slugify_href("notes/!!!.html"), "notes/212121.html");
+    }
+
+    #[test]
+    fn collisions_test() {
+        let hrefs = [
+            ("my-note.html".to_owned(), "My Note.md"),
+            ("a.html".to_owned(), "a.md"),
+            ("series/c.html".to_owned(), "C"),
+            ("my-note.html".to_owned(), "my-note.md"),
+            ("series/c.html".to_owned(), "C++"),
+        ];
+
+        assert_eq!(
+            collisions(hrefs),
+            [
+                ("my-note.html".to_owned(), vec!["My Note.md", "my-note.md"]),
+                ("series/c.html".to_owned(), vec!["C", "C++"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_test() {
+        assert_eq!(encode("blog/post.html"), "blog/post.html");
+        assert_eq!(encode("My Note #1?.html"), "My%20Note%20%231%3F.html");
+        assert_eq!(encode("de/über.html"), "de/%C3%BCber.html");
+        assert_eq!(encode("100%.html"), "100%25.html");
     }
 }
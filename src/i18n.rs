@@ -0,0 +1,630 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Translations of the messages whim shows on the command line. The locale is
+//! chosen from the `LC_ALL`, `LC_MESSAGES`, and `LANG` environment variables,
+//! in that order, falling back to English for any locale without a
+//! translation. Log records are not translated.
+//!
+//! Messages are templates in which each "{}" is replaced by an argument, in
+//! order, by [`Message::format`].
+//!
+//! [`Message::format`]: Message::format
+
+use std::{env, fmt::Display, sync::OnceLock};
+
+/// Environment variables which may name the locale, most important first.
+const LOCALE_VARS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Placeholder replaced by arguments in message templates.
+const PLACEHOLDER: &str = "{}";
+
+/// The locale of the process, see [`locale`].
+///
+/// [`locale`]: locale
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// A language whim has translations for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+    /// Gets the [`Locale`] of the given POSIX locale name, e.g. "de_DE.UTF-8",
+    /// returning [`None`] if there is no translation for it.
+    ///
+    /// [`Locale`]: Locale
+    /// [`None`]: None
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let language = name
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Self::English),
+            "de" => Some(Self::German),
+            _ => None,
+        }
+    }
+
+    /// Gets the [`Locale`] named by the environment, see the [module]
+    /// documentation.
+    ///
+    /// [`Locale`]: Locale
+    /// [module]: crate::i18n
+    #[must_use]
+    pub fn from_env() -> Self {
+        LOCALE_VARS
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|name| !name.is_empty())
+            .and_then(|name| Self::from_name(&name))
+            .unwrap_or_default()
+    }
+
+    /// Gets the answers to yes or no prompts taken as "yes" in this
+    /// [`Locale`], the first being the one shown in their options.
+    ///
+    /// [`Locale`]: Locale
+    #[must_use]
+    pub const fn yes_answers(self) -> &'static [&'static str] {
+        match self {
+            Self::English => &["y", "yes"],
+            Self::German => &["j", "ja"],
+        }
+    }
+
+    /// Gets the answers to yes or no prompts taken as "no" in this
+    /// [`Locale`], the first being the one shown in their options.
+    ///
+    /// [`Locale`]: Locale
+    #[must_use]
+    pub const fn no_answers(self) -> &'static [&'static str] {
+        match self {
+            Self::English => &["n", "no"],
+            Self::German => &["n", "nein"],
+        }
+    }
+}
+
+/// Gets the [`Locale`] messages are shown in, read from the environment the
+/// first time it is needed.
+///
+/// [`Locale`]: Locale
+#[must_use]
+pub fn locale() -> Locale {
+    *LOCALE.get_or_init(Locale::from_env)
+}
+
+/// A message shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    LibraryExists,
+    FoundDocuments,
+    FoundNoDocuments,
+    CreateLibrary,
+    SiteTitle,
+    RelatedCount,
+    RecentCount,
+    DocumentsChanged,
+    UpdatedDocuments,
+    NoUpdates,
+    FoundNewDocuments,
+    AddedDocuments,
+    Added,
+    FailedToAdd,
+    CouldNotUpdateLibrary,
+    FoundNoNewDocuments,
+    WhichDocument,
+    GiveDocumentToAdd,
+    CouldNotAdd,
+    AddedPath,
+    CouldNotSaveAdd,
+    OutputDirectory,
+    PreBuildHookFailed,
+    CouldNotLoadScripts,
+    ScriptFailed,
+    InvalidFrontMatter,
+    CouldNotReadDocuments,
+    CouldNotReadTheme,
+    CouldNotReadData,
+    CouldNotProtect,
+    HrefCollision,
+    CouldNotOptimizeImages,
+    WroteHtml,
+    CouldNotWriteHtml,
+    PostBuildHookFailed,
+    NoLibrary,
+    InvalidLibrary,
+    Rehashed,
+    CouldNotSaveRehashed,
+    LibraryToMerge,
+    MergeWhichLibrary,
+    CouldNotReadMerged,
+    KeptOurs,
+    KeptTheirs,
+    Merged,
+    CouldNotSaveMerged,
+    QueuedMentions,
+    CouldNotQueueMentions,
+    CouldNotReadMentions,
+    NoMentions,
+    Sent,
+    FailedToSend,
+    SentMentions,
+    ExportWhichDocument,
+    NoPdfRenderer,
+    NotInLibrary,
+    Exported,
+    FailedToExport,
+    ExportedPdfs,
+    WroteEpub,
+    CouldNotWriteEpub,
+    CouldNotOpenLogFile,
+    CouldNotReadConfig,
+    SingleCommand,
+    AnswerOneOf,
+    EnterValid,
+    SelectOne,
+    SelectOneOrDefault,
+    EnterNumber,
+    SelectMany,
+    All,
+    None,
+    EnterNumbersOrRanges,
+    DoesNotExist,
+    OutOfRange,
+    NotFromTo,
+    NotFromUpTo,
+    NotAtLeast,
+    NotGreaterThan,
+    NotAtMost,
+    NotLessThan,
+    InputBlank,
+    YesOptions,
+    NoOptions,
+    TextOptions,
+    PathOptions,
+    IntegerOptions,
+    NumberOptions,
+    Help,
+}
+
+impl Message {
+    /// Gets the template of this [`Message`] in the process' [`Locale`].
+    ///
+    /// [`Message`]: Message
+    /// [`Locale`]: Locale
+    #[inline]
+    #[must_use]
+    pub fn text(self) -> &'static str {
+        self.text_in(locale())
+    }
+
+    /// Gets the template of this [`Message`] in the given [`Locale`].
+    ///
+    /// [`Message`]: Message
+    /// [`Locale`]: Locale
+    #[must_use]
+    pub fn text_in(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::English => self.english(),
+            Locale::German => self.german(),
+        }
+    }
+
+    /// Fills the template of this [`Message`] with the given arguments.
+    ///
+    /// [`Message`]: Message
+    #[inline]
+    #[must_use]
+    pub fn format(self, args: &[&dyn Display]) -> String {
+        fill(self.text(), args)
+    }
+
+    const fn english(self) -> &'static str {
+        match self {
+            Self::LibraryExists => "a library already exists in the current directory, overwrite it",
+            Self::FoundDocuments => "whim found {} markdown documents in the current directory:",
+            Self::FoundNoDocuments => "whim found no markdown documents in the current directory",
+            Self::CreateLibrary => "create a new library with {} documents",
+            Self::SiteTitle => "site title",
+            Self::RelatedCount => "related documents listed on each page",
+            Self::RecentCount => "recently updated documents listed on indexes",
+            Self::DocumentsChanged => "{} documents have changed:",
+            Self::UpdatedDocuments => "updated {} documents in library",
+            Self::NoUpdates => "no updates to make",
+            Self::FoundNewDocuments => "found {} documents not in the library:",
+            Self::AddedDocuments => "added {} documents to library",
+            Self::Added => "added",
+            Self::FailedToAdd => "failed to add",
+            Self::CouldNotUpdateLibrary => "could not update library with new documents",
+            Self::FoundNoNewDocuments => "found no documents not already in library",
+            Self::WhichDocument => "which document should be added?",
+            Self::GiveDocumentToAdd => "no document to add can be assumed, give one as an argument",
+            Self::CouldNotAdd => "could not add '{}'",
+            Self::AddedPath => "added '{}'",
+            Self::CouldNotSaveAdd => "could not save library, add failed",
+            Self::OutputDirectory => "output directory",
+            Self::PreBuildHookFailed => "a pre-build hook failed, stopping build",
+            Self::CouldNotLoadScripts => "could not load scripts",
+            Self::ScriptFailed => "a script failed to transform a page",
+            Self::InvalidFrontMatter => "a document's front matter could not be parsed, see above",
+            Self::CouldNotReadDocuments => "could not read all documents for parsing",
+            Self::CouldNotReadTheme => "could not read the theme directory",
+            Self::CouldNotReadData => "could not read the data files, see above",
+            Self::CouldNotProtect => "could not encrypt a protected page, see above",
+            Self::HrefCollision => "several pages would be written to the same file, see above",
+            Self::CouldNotOptimizeImages => "could not optimize an image, see above",
+            Self::WroteHtml => "wrote HTML to '{}'",
+            Self::CouldNotWriteHtml => "could not write HTML to '{}'",
+            Self::PostBuildHookFailed => "a post-build hook failed",
+            Self::NoLibrary => "whim could not open a library in the current directory, you may need to create one with `whim new`",
+            Self::InvalidLibrary => "the library in the current directory could not be read, see above",
+            Self::Rehashed => "rehashed {} documents with {}",
+            Self::CouldNotSaveRehashed => "could not save library with rehashed documents",
+            Self::LibraryToMerge => "library to merge",
+            Self::MergeWhichLibrary => "give the path of a library to merge, e.g. `whim merge ../notes/.whim.ron`",
+            Self::CouldNotReadMerged => "could not read the library '{}' to merge",
+            Self::KeptOurs => "kept ours",
+            Self::KeptTheirs => "kept theirs",
+            Self::Merged => "merged {} new documents into the library, {} differed between libraries",
+            Self::CouldNotSaveMerged => "could not save library, merge failed",
+            Self::QueuedMentions => "queued {} webmentions, send them with `whim webmention send` once the site is published",
+            Self::CouldNotQueueMentions => "could not queue webmentions",
+            Self::CouldNotReadMentions => "could not read queued webmentions",
+            Self::NoMentions => "no webmentions to send",
+            Self::Sent => "sent",
+            Self::FailedToSend => "failed to send",
+            Self::SentMentions => "sent {} of {} webmentions",
+            Self::ExportWhichDocument => "which document should be exported?",
+            Self::NoPdfRenderer => "could not find a browser or wkhtmltopdf to render PDFs with, set `pdf_renderer` in .whim.toml",
+            Self::NotInLibrary => "'{}' is not in the library",
+            Self::Exported => "exported",
+            Self::FailedToExport => "failed to export",
+            Self::ExportedPdfs => "exported {} of {} documents to '{}'",
+            Self::WroteEpub => "wrote {} documents to '{}'",
+            Self::CouldNotWriteEpub => "could not write EPUB to '{}'",
+            Self::CouldNotOpenLogFile => "could not open log file '{}'",
+            Self::CouldNotReadConfig => "could not read configuration from '{}'",
+            Self::SingleCommand => "Only singlular commands permitted.",
+            Self::AnswerOneOf => "please answer with one of [{}]",
+            Self::EnterValid => "please enter a valid {}",
+            Self::SelectOne => "select [1-{}] ?",
+            Self::SelectOneOrDefault => "select [1-{}, default {}] ?",
+            Self::EnterNumber => "please enter a number from 1 to {}",
+            Self::SelectMany => "select [numbers or ranges, a for all, n for none, default {}] ?",
+            Self::All => "all",
+            Self::None => "none",
+            Self::EnterNumbersOrRanges => "please enter numbers or ranges from 1 to {}, 'a', or 'n'",
+            Self::DoesNotExist => "'{}' does not exist",
+            Self::OutOfRange => "{} is out of range",
+            Self::NotFromTo => "{} is not from {} to {}",
+            Self::NotFromUpTo => "{} is not from {} up to but excluding {}",
+            Self::NotAtLeast => "{} is not at least {}",
+            Self::NotGreaterThan => "{} is not greater than {}",
+            Self::NotAtMost => "{} is not at most {}",
+            Self::NotLessThan => "{} is not less than {}",
+            Self::InputBlank => "input may not be blank",
+            Self::YesOptions => "Y/n",
+            Self::NoOptions => "y/N",
+            Self::TextOptions => "text",
+            Self::PathOptions => "path",
+            Self::IntegerOptions => "integer",
+            Self::NumberOptions => "number",
+            Self::Help => "\
+                whim\n\
+                \n\
+                Usage: whim [COMMAND]\n\
+                \n\
+                Commands:\n\
+                \tnew      Creates new library in the current directory.\n\
+                \tupdate   Updates the library in the current directory.\n\
+                \tscan     Scans the directory for new files.\n\
+                \tadd      Add a document, or choose one not yet added.\n\
+                \twebmention [send]\n\
+                \t         List queued webmentions, or send them.\n\
+                \texport-pdf [DOCUMENT|all] [DIR]\n\
+                \t         Export documents as PDFs, to pdf/ by default.\n\
+                \tepub [PATH] [DOCUMENT...]\n\
+                \t         Bundle documents, or every document, into an EPUB.\n\
+                \tmerge LIBRARY\n\
+                \t         Merge the documents of another library file into this one.\n\
+                \n\
+                Options:\n\
+                \t-y, --yes        Answer every prompt with yes or its default, as\n\
+                \t                 does setting WHIM_ASSUME_YES.\n\
+                \t-v, --verbose    Log more of what whim is doing, may be repeated.\n\
+                \t--log-file PATH  Append the log to the given file as well.\n\
+                \t--config PATH    Read the project configuration from the given\n\
+                \t                 file rather than .whim.toml.\
+                ",
+        }
+    }
+
+    const fn german(self) -> &'static str {
+        match self {
+            Self::LibraryExists => "im aktuellen Verzeichnis gibt es bereits eine Bibliothek, überschreiben",
+            Self::FoundDocuments => "whim hat {} Markdown-Dokumente im aktuellen Verzeichnis gefunden:",
+            Self::FoundNoDocuments => "whim hat keine Markdown-Dokumente im aktuellen Verzeichnis gefunden",
+            Self::CreateLibrary => "eine neue Bibliothek mit {} Dokumenten erstellen",
+            Self::SiteTitle => "Titel der Seite",
+            Self::RelatedCount => "verwandte Dokumente auf jeder Seite",
+            Self::RecentCount => "kürzlich aktualisierte Dokumente auf Indexseiten",
+            Self::DocumentsChanged => "{} Dokumente haben sich geändert:",
+            Self::UpdatedDocuments => "{} Dokumente in der Bibliothek aktualisiert",
+            Self::NoUpdates => "keine Aktualisierungen nötig",
+            Self::FoundNewDocuments => "{} Dokumente gefunden, die nicht in der Bibliothek sind:",
+            Self::AddedDocuments => "{} Dokumente zur Bibliothek hinzugefügt",
+            Self::Added => "hinzugefügt",
+            Self::FailedToAdd => "nicht hinzugefügt",
+            Self::CouldNotUpdateLibrary => "die Bibliothek konnte nicht mit den neuen Dokumenten aktualisiert werden",
+            Self::FoundNoNewDocuments => "keine Dokumente gefunden, die nicht bereits in der Bibliothek sind",
+            Self::WhichDocument => "welches Dokument soll hinzugefügt werden?",
+            Self::GiveDocumentToAdd => "es kann kein hinzuzufügendes Dokument angenommen werden, gib eines als Argument an",
+            Self::CouldNotAdd => "'{}' konnte nicht hinzugefügt werden",
+            Self::AddedPath => "'{}' hinzugefügt",
+            Self::CouldNotSaveAdd => "die Bibliothek konnte nicht gespeichert werden, Hinzufügen fehlgeschlagen",
+            Self::OutputDirectory => "Ausgabeverzeichnis",
+            Self::PreBuildHookFailed => "ein Pre-Build-Hook ist fehlgeschlagen, Build wird abgebrochen",
+            Self::CouldNotLoadScripts => "die Skripte konnten nicht geladen werden",
+            Self::ScriptFailed => "ein Skript konnte eine Seite nicht umwandeln",
+            Self::InvalidFrontMatter => "die Front Matter eines Dokuments konnte nicht gelesen werden, siehe oben",
+            Self::CouldNotReadDocuments => "nicht alle Dokumente konnten zum Verarbeiten gelesen werden",
+            Self::CouldNotReadTheme => "das Theme-Verzeichnis konnte nicht gelesen werden",
+            Self::CouldNotReadData => "die Datendateien konnten nicht gelesen werden, siehe oben",
+            Self::CouldNotProtect => "eine geschützte Seite konnte nicht verschlüsselt werden, siehe oben",
+            Self::HrefCollision => "mehrere Seiten würden in dieselbe Datei geschrieben, siehe oben",
+            Self::CouldNotOptimizeImages => "ein Bild konnte nicht optimiert werden, siehe oben",
+            Self::WroteHtml => "HTML nach '{}' geschrieben",
+            Self::CouldNotWriteHtml => "HTML konnte nicht nach '{}' geschrieben werden",
+            Self::PostBuildHookFailed => "ein Post-Build-Hook ist fehlgeschlagen",
+            Self::NoLibrary => "whim konnte im aktuellen Verzeichnis keine Bibliothek öffnen, eventuell muss mit `whim new` eine erstellt werden",
+            Self::InvalidLibrary => "die Bibliothek im aktuellen Verzeichnis konnte nicht gelesen werden, siehe oben",
+            Self::Rehashed => "{} Dokumente mit {} neu gehasht",
+            Self::CouldNotSaveRehashed => "die Bibliothek mit neu gehashten Dokumenten konnte nicht gespeichert werden",
+            Self::LibraryToMerge => "zusammenzuführende Bibliothek",
+            Self::MergeWhichLibrary => "gib den Pfad einer zusammenzuführenden Bibliothek an, z. B. `whim merge ../notes/.whim.ron`",
+            Self::CouldNotReadMerged => "die zusammenzuführende Bibliothek '{}' konnte nicht gelesen werden",
+            Self::KeptOurs => "unsere behalten",
+            Self::KeptTheirs => "ihre behalten",
+            Self::Merged => "{} neue Dokumente in die Bibliothek zusammengeführt, {} unterschieden sich zwischen den Bibliotheken",
+            Self::CouldNotSaveMerged => "die Bibliothek konnte nicht gespeichert werden, Zusammenführen fehlgeschlagen",
+            Self::QueuedMentions => "{} Webmentions vorgemerkt, nach dem Veröffentlichen mit `whim webmention send` senden",
+            Self::CouldNotQueueMentions => "Webmentions konnten nicht vorgemerkt werden",
+            Self::CouldNotReadMentions => "vorgemerkte Webmentions konnten nicht gelesen werden",
+            Self::NoMentions => "keine Webmentions zu senden",
+            Self::Sent => "gesendet",
+            Self::FailedToSend => "nicht gesendet",
+            Self::SentMentions => "{} von {} Webmentions gesendet",
+            Self::ExportWhichDocument => "welches Dokument soll exportiert werden?",
+            Self::NoPdfRenderer => "kein Browser oder wkhtmltopdf zum Erstellen von PDFs gefunden, setze `pdf_renderer` in .whim.toml",
+            Self::NotInLibrary => "'{}' ist nicht in der Bibliothek",
+            Self::Exported => "exportiert",
+            Self::FailedToExport => "nicht exportiert",
+            Self::ExportedPdfs => "{} von {} Dokumenten nach '{}' exportiert",
+            Self::WroteEpub => "{} Dokumente nach '{}' geschrieben",
+            Self::CouldNotWriteEpub => "EPUB konnte nicht nach '{}' geschrieben werden",
+            Self::CouldNotOpenLogFile => "Logdatei '{}' konnte nicht geöffnet werden",
+            Self::CouldNotReadConfig => "Konfiguration konnte nicht aus '{}' gelesen werden",
+            Self::SingleCommand => "Nur ein einzelner Befehl ist erlaubt.",
+            Self::AnswerOneOf => "bitte mit einem von [{}] antworten",
+            Self::EnterValid => "bitte einen gültigen Wert ({}) eingeben",
+            Self::SelectOne => "auswählen [1-{}] ?",
+            Self::SelectOneOrDefault => "auswählen [1-{}, Standard {}] ?",
+            Self::EnterNumber => "bitte eine Zahl von 1 bis {} eingeben",
+            Self::SelectMany => "auswählen [Zahlen oder Bereiche, a für alle, n für keine, Standard {}] ?",
+            Self::All => "alle",
+            Self::None => "keine",
+            Self::EnterNumbersOrRanges => "bitte Zahlen oder Bereiche von 1 bis {}, 'a' oder 'n' eingeben",
+            Self::DoesNotExist => "'{}' existiert nicht",
+            Self::OutOfRange => "{} liegt außerhalb des Bereichs",
+            Self::NotFromTo => "{} liegt nicht zwischen {} und {}",
+            Self::NotFromUpTo => "{} liegt nicht zwischen {} und ausschließlich {}",
+            Self::NotAtLeast => "{} ist nicht mindestens {}",
+            Self::NotGreaterThan => "{} ist nicht größer als {}",
+            Self::NotAtMost => "{} ist nicht höchstens {}",
+            Self::NotLessThan => "{} ist nicht kleiner als {}",
+            Self::InputBlank => "die Eingabe darf nicht leer sein",
+            Self::YesOptions => "J/n",
+            Self::NoOptions => "j/N",
+            Self::TextOptions => "Text",
+            Self::PathOptions => "Pfad",
+            Self::IntegerOptions => "Ganzzahl",
+            Self::NumberOptions => "Zahl",
+            Self::Help => "\
+                whim\n\
+                \n\
+                Verwendung: whim [BEFEHL]\n\
+                \n\
+                Befehle:\n\
+                \tnew      Erstellt eine neue Bibliothek im aktuellen Verzeichnis.\n\
+                \tupdate   Aktualisiert die Bibliothek im aktuellen Verzeichnis.\n\
+                \tscan     Durchsucht das Verzeichnis nach neuen Dateien.\n\
+                \tadd      Fügt ein Dokument hinzu, oder wählt eines noch nicht hinzugefügtes.\n\
+                \twebmention [send]\n\
+                \t         Listet vorgemerkte Webmentions auf, oder sendet sie.\n\
+                \texport-pdf [DOKUMENT|all] [VERZEICHNIS]\n\
+                \t         Exportiert Dokumente als PDFs, standardmäßig nach pdf/.\n\
+                \tepub [PFAD] [DOKUMENT...]\n\
+                \t         Bündelt Dokumente, oder alle Dokumente, zu einem EPUB.\n\
+                \tmerge BIBLIOTHEK\n\
+                \t         Führt die Dokumente einer anderen Bibliotheksdatei mit dieser zusammen.\n\
+                \n\
+                Optionen:\n\
+                \t-y, --yes        Beantwortet jede Frage mit ja oder ihrer Vorgabe, wie\n\
+                \t                 auch das Setzen von WHIM_ASSUME_YES.\n\
+                \t-v, --verbose    Protokolliert mehr davon, was whim tut, wiederholbar.\n\
+                \t--log-file PFAD  Hängt das Protokoll auch an die gegebene Datei an.\n\
+                \t--config PFAD    Liest die Projektkonfiguration aus der gegebenen\n\
+                \t                 Datei statt aus .whim.toml.\
+                ",
+        }
+    }
+}
+
+/// Replaces each "{}" in the given template with the next of the given
+/// arguments. Placeholders beyond the last argument are left as they are.
+#[must_use]
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+
+    while let Some(i) = rest.find(PLACEHOLDER) {
+        filled.push_str(&rest[..i]);
+
+        match args.next() {
+            Some(arg) => filled.push_str(&arg.to_string()),
+            None => filled.push_str(PLACEHOLDER),
+        }
+
+        rest = &rest[i + PLACEHOLDER.len()..];
+    }
+
+    filled.push_str(rest);
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_test() {
+        assert_eq!(Locale::from_name("de_DE.UTF-8"), Some(Locale::German));
+        assert_eq!(Locale::from_name("de-AT"), Some(Locale::German));
+        assert_eq!(Locale::from_name("en_US"), Some(Locale::English));
+        assert_eq!(Locale::from_name("C"), Some(Locale::English));
+        assert_eq!(Locale::from_name("fr_FR"), None);
+
+        // Options shown with yes or no prompts must be their answers.
+        for locale in [Locale::English, Locale::German] {
+            let options = Message::YesOptions.text_in(locale).to_lowercase();
+            assert_eq!(
+                options,
+                format!("{}/{}", locale.yes_answers()[0], locale.no_answers()[0])
+            );
+            assert_eq!(options, Message::NoOptions.text_in(locale).to_lowercase());
+        }
+    }
+
+    #[test]
+    fn fill_test() {
+        assert_eq!(fill("added {} of {}", &[&1, &"two"]), "added 1 of two");
+        assert_eq!(fill("{} and {}", &[&1]), "1 and {}");
+        assert_eq!(fill("no placeholders", &[&1]), "no placeholders");
+
+        // Every translation must take the same arguments as the English.
+        let messages = [
+            Message::LibraryExists,
+            Message::FoundDocuments,
+            Message::FoundNoDocuments,
+            Message::CreateLibrary,
+            Message::SiteTitle,
+            Message::RelatedCount,
+            Message::RecentCount,
+            Message::DocumentsChanged,
+            Message::UpdatedDocuments,
+            Message::NoUpdates,
+            Message::FoundNewDocuments,
+            Message::AddedDocuments,
+            Message::Added,
+            Message::FailedToAdd,
+            Message::CouldNotUpdateLibrary,
+            Message::FoundNoNewDocuments,
+            Message::WhichDocument,
+            Message::GiveDocumentToAdd,
+            Message::CouldNotAdd,
+            Message::AddedPath,
+            Message::CouldNotSaveAdd,
+            Message::OutputDirectory,
+            Message::PreBuildHookFailed,
+            Message::CouldNotLoadScripts,
+            Message::ScriptFailed,
+            Message::InvalidFrontMatter,
+            Message::CouldNotReadDocuments,
+            Message::CouldNotReadTheme,
+            Message::CouldNotReadData,
+            Message::CouldNotProtect,
+            Message::HrefCollision,
+            Message::CouldNotOptimizeImages,
+            Message::WroteHtml,
+            Message::CouldNotWriteHtml,
+            Message::PostBuildHookFailed,
+            Message::NoLibrary,
+            Message::InvalidLibrary,
+            Message::Rehashed,
+            Message::CouldNotSaveRehashed,
+            Message::LibraryToMerge,
+            Message::MergeWhichLibrary,
+            Message::CouldNotReadMerged,
+            Message::KeptOurs,
+            Message::KeptTheirs,
+            Message::Merged,
+            Message::CouldNotSaveMerged,
+            Message::QueuedMentions,
+            Message::CouldNotQueueMentions,
+            Message::CouldNotReadMentions,
+            Message::NoMentions,
+            Message::Sent,
+            Message::FailedToSend,
+            Message::SentMentions,
+            Message::ExportWhichDocument,
+            Message::NoPdfRenderer,
+            Message::NotInLibrary,
+            Message::Exported,
+            Message::FailedToExport,
+            Message::ExportedPdfs,
+            Message::WroteEpub,
+            Message::CouldNotWriteEpub,
+            Message::CouldNotOpenLogFile,
+            Message::CouldNotReadConfig,
+            Message::SingleCommand,
+            Message::AnswerOneOf,
+            Message::EnterValid,
+            Message::SelectOne,
+            Message::SelectOneOrDefault,
+            Message::EnterNumber,
+            Message::SelectMany,
+            Message::All,
+            Message::None,
+            Message::EnterNumbersOrRanges,
+            Message::DoesNotExist,
+            Message::OutOfRange,
+            Message::NotFromTo,
+            Message::NotFromUpTo,
+            Message::NotAtLeast,
+            Message::NotGreaterThan,
+            Message::NotAtMost,
+            Message::NotLessThan,
+            Message::InputBlank,
+            Message::YesOptions,
+            Message::NoOptions,
+            Message::TextOptions,
+            Message::PathOptions,
+            Message::IntegerOptions,
+            Message::NumberOptions,
+            Message::Help,
+        ];
+
+        for message in messages {
+            assert_eq!(
+                message.text_in(Locale::German).matches(PLACEHOLDER).count(),
+                message
+                    .text_in(Locale::English)
+                    .matches(PLACEHOLDER)
+                    .count(),
+                "{:?}",
+                message
+            );
+        }
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Optimization of the images documents reference, enabled by setting
+//! `images` under `site`. Each local JPEG or PNG image is scaled down to fit
+//! a maximum width and recompressed, and given a WebP variant which pages
+//! prefer if it is smaller. Other images are copied as they are. Results are
+//! cached by the hash of each image and the settings it was optimized with,
+//! so images are only processed again once they or the settings change.
+
+use crate::{hash::HashAlgorithm, href};
+use image::{
+    codecs::{
+        jpeg::JpegEncoder,
+        png::{self, PngEncoder},
+        webp::WebPEncoder,
+    },
+    imageops::FilterType,
+    DynamicImage,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    error, fmt, fs,
+    path::{Path, PathBuf},
+    result,
+};
+
+/// Directory, relative to the library, optimized images are cached in.
+pub const CACHE_DIR: &str = ".whim-images";
+
+/// Settings of image optimization.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Images {
+    /// Width, in pixels, wider images are scaled down to.
+    max_width: u32,
+
+    /// Quality of recompressed JPEG images, from 1 to 100.
+    quality: u8,
+
+    /// Whether to give images WebP variants.
+    webp: bool,
+}
+
+impl Default for Images {
+    fn default() -> Self {
+        Self {
+            max_width: 1600,
+            quality: 80,
+            webp: true,
+        }
+    }
+}
+
+/// An image ready to be copied into the output directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Optimized {
+    /// Path of the image, which may be the original if it was not optimized.
+    pub path: PathBuf,
+
+    /// Path of the image's WebP variant, if it has one smaller than it.
+    pub webp: Option<PathBuf>,
+}
+
+impl Images {
+    /// Optimizes the image at the given path, using the cached result if it
+    /// was optimized before with the same settings. Images other than JPEG
+    /// and PNG images are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image could not be read or decoded, or the
+    /// result could not be cached.
+    pub fn optimize(&self, source: &Path, cache: &Path) -> Result<Optimized> {
+        let ext = source
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if !matches!(ext.as_str(), "jpg" | "jpeg" | "png") {
+            return Ok(Optimized {
+                path: source.to_path_buf(),
+                webp: None,
+            });
+        }
+
+        let bytes = fs::read(source).map_err(|_| Error::FileReadError)?;
+        let key = format!(
+            "{}-{}-{}",
+            HashAlgorithm::Blake3x128.hash(&bytes),
+            self.max_width,
+            self.quality
+        );
+
+        let path = cache.join(format!("{}.{}", key, ext));
+        let webp = cache.join(format!("{}.webp", key));
+
+        let cached = path.exists() && (!self.webp || webp.exists());
+
+        if !cached {
+            log::info!("optimizing '{}'", source.display());
+            self.encode(&bytes, &ext, &path, &webp)?;
+        }
+
+        // Variants are kept even when larger, so the comparison is cached too.
+        let size = |path: &Path| fs::metadata(path).map(|m| m.len()).ok();
+        let webp = match self.webp && size(&webp) < size(&path) {
+            true => Some(webp),
+            false => None,
+        };
+
+        Ok(Optimized { path, webp })
+    }
+
+    /// Decodes, scales, and encodes the given image, writing it and its WebP
+    /// variant to the given paths. The original is written as it was if
+    /// recompressing it only made it larger.
+    fn encode(&self, bytes: &[u8], ext: &str, path: &Path, webp: &Path) -> Result<()> {
+        let image = image::load_from_memory(bytes).map_err(|_| Error::DecodeError)?;
+        let resized = image.width() > self.max_width;
+
+        let image = match resized {
+            true => image.resize(self.max_width, u32::MAX, FilterType::Lanczos3),
+            false => image,
+        };
+
+        let mut encoded = Vec::new();
+
+        match ext {
+            "png" => image.write_with_encoder(PngEncoder::new_with_quality(
+                &mut encoded,
+                png::CompressionType::Best,
+                png::FilterType::Adaptive,
+            )),
+            _ => DynamicImage::from(image.to_rgb8())
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut encoded, self.quality)),
+        }
+        .map_err(|_| Error::EncodeError)?;
+
+        let encoded = match resized || encoded.len() < bytes.len() {
+            true => encoded.as_slice(),
+            false => bytes,
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|_| Error::DirectoryCreateError)?;
+        }
+
+        fs::write(path, encoded).map_err(|_| Error::FileWriteError)?;
+
+        if self.webp {
+            let mut variant = Vec::new();
+            image
+                .write_with_encoder(WebPEncoder::new_lossless(&mut variant))
+                .map_err(|_| Error::EncodeError)?;
+            fs::write(webp, variant).map_err(|_| Error::FileWriteError)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves the given image source from a document to the path of the image,
+/// relative to the library's directory. Returns [`None`] for images on other
+/// sites or at absolute paths.
+///
+/// [`None`]: None
+#[must_use]
+pub fn resolve(doc: &str, src: &str) -> Option<String> {
+    match src.is_empty() || src.contains(':') || src.starts_with('/') {
+        true => None,
+        false => {
+            let dir = Path::new(doc).parent().unwrap_or(Path::new(""));
+            Some(href::from_path(&dir.join(src)))
+        }
+    }
+}
+
+/// Wraps each image with a WebP variant, in the page content of the document
+/// at the given path, in a `picture` element offering the variant first.
+/// Variants are given by the href of their image, both being relative to the
+/// root of the output directory.
+#[must_use]
+pub fn pictures(content: &str, doc: &str, variants: &BTreeMap<String, String>) -> String {
+    const IMG: &str = "<img src=\"";
+
+    let mut pictured = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find(IMG) {
+        let src = &rest[start + IMG.len()..];
+        let (src, end) = match (src.find('"'), rest[start..].find('>')) {
+            (Some(src_end), Some(end)) => (&src[..src_end], start + end + 1),
+            _ => break,
+        };
+
+        pictured.push_str(&rest[..start]);
+
+        match resolve(doc, src).and_then(|href| variants.get(&href)) {
+            Some(webp) => {
+                let dir = Path::new(src).parent().unwrap_or(Path::new(""));
+                let name = Path::new(webp).file_name().unwrap_or_default();
+                pictured.push_str(&format!(
+                    "<picture><source srcset=\"{}\" type=\"image/webp\">{}</picture>",
+                    href::from_path(&dir.join(name)),
+                    &rest[start..end]
+                ));
+            }
+            None => pictured.push_str(&rest[start..end]),
+        }
+
+        rest = &rest[end..];
+    }
+
+    pictured + rest
+}
+
+/// The result type of image optimization related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while optimizing images.
+#[derive(Debug)]
+pub enum Error {
+    /// I/O failure to read an image.
+    FileReadError,
+
+    /// I/O failure to write an optimized image.
+    FileWriteError,
+
+    /// I/O failure to create the cache directory.
+    DirectoryCreateError,
+
+    /// An image is not a valid JPEG or PNG image.
+    DecodeError,
+
+    /// An image could not be encoded.
+    EncodeError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pictures_test() {
+        let variants =
+            BTreeMap::from([("blog/img/a.png".to_owned(), "blog/img/a.webp".to_owned())]);
+        let content = r#"<p><img src="img/a.png" alt="A" /><img src="b.png" alt="B" /></p>"#;
+
+        assert_eq!(
+            pictures(content, "blog/post.md", &variants),
+            concat!(
+                r#"<p><picture><source srcset="img/a.webp" type="image/webp">"#,
+                r#"<img src="img/a.png" alt="A" /></picture><img src="b.png" alt="B" /></p>"#
+            )
+        );
+        assert_eq!(
+            resolve("blog/post.md", "../a.png").as_deref(),
+            Some("a.png")
+        );
+        assert_eq!(resolve("post.md", "https://example.com/a.png"), None);
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Support for sites translated into multiple languages. A document's language
+//! is given either by keeping it in a directory named after the language (e.g.
+//! "de/about.md") or by a language suffix on its file name (e.g.
+//! "about.de.md"). Each language is written to its own directory of the
+//! output, with translations of the same document sharing a path within it.
+
+use std::rc::Rc;
+
+/// Gets the language of the document at the given path and the path shared by
+/// all of its translations, relative to the language's directory. Documents
+/// with neither a language directory nor suffix are in the first, default,
+/// language. Returns [`None`] if no languages are given.
+///
+/// [`None`]: None
+#[must_use]
+pub fn split<'a>(path: &str, languages: &'a [Rc<str>]) -> Option<(&'a str, String)> {
+    let default = languages.first()?;
+    let find = |name: &str| languages.iter().find(|l| ***l == *name);
+
+    if let Some((dir, rest)) = path.split_once('/') {
+        if let Some(lang) = find(dir) {
+            return Some((lang, rest.to_owned()));
+        }
+    }
+
+    let (dir, file) = match path.rsplit_once('/') {
+        Some((dir, file)) => (Some(dir), file),
+        None => (None, path),
+    };
+
+    let suffixed = file
+        .strip_suffix(".md")
+        .and_then(|stem| stem.rsplit_once('.'))
+        .and_then(|(name, suffix)| Some((find(suffix)?, name)));
+
+    Some(match suffixed {
+        Some((lang, name)) => match dir {
+            Some(dir) => (lang, format!("{}/{}.md", dir, name)),
+            None => (lang, format!("{}.md", name)),
+        },
+        None => (default, path.to_owned()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_test() {
+        let languages: Vec<Rc<str>> = vec!["en".into(), "de".into()];
+
+        assert_eq!(split("a.md", &[]), None);
+        assert_eq!(split("a.md", &languages), Some(("en", "a.md".to_owned())));
+        assert_eq!(
+            split("de/blog/a.md", &languages),
+            Some(("de", "blog/a.md".to_owned()))
+        );
+        assert_eq!(
+            split("blog/a.de.md", &languages),
+            Some(("de", "blog/a.md".to_owned()))
+        );
+        assert_eq!(
+            split("blog/a.fr.md", &languages),
+            Some(("en", "blog/a.fr.md".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Layouts wrapping the content of document pages. A layout is an HTML file
+//! in the `templates` directory of the theme directory, chosen by a document
+//! with `layout` in its front matter, and `default.html` is used for documents
+//! which choose none. Fields such as `{{ content }}` are filled in with the
+//! document's, as are fields naming a value of the site's [data], and
+//! documents without a layout keep their content as it is.
+//!
+//! [data]: crate::data
+
+use crate::data::Data;
+use std::{collections::HashMap, error, fmt, fs, path::Path, result};
+
+/// Directory of layouts within the theme directory, not copied into the
+/// output.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Name of the layout used by documents which do not choose one.
+const DEFAULT_LAYOUT: &str = "default";
+
+/// Layouts by name, see the [module] documentation.
+///
+/// [module]: crate::layout
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layouts {
+    templates: HashMap<String, String>,
+}
+
+impl Layouts {
+    /// Reads every layout in the templates directory of the given theme
+    /// directory, which may not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a layout could not be read.
+    pub fn load(theme: impl AsRef<Path>) -> Result<Self> {
+        let dir = theme.as_ref().join(TEMPLATES_DIR);
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(Self::default()),
+        };
+
+        let mut templates = HashMap::new();
+
+        for path in entries.filter_map(|e| Some(e.ok()?.path())) {
+            let name = match path.extension().is_some_and(|ext| ext == "html") {
+                true => path.file_stem().map(|s| s.to_string_lossy().into_owned()),
+                false => None,
+            };
+
+            if let Some(name) = name {
+                log::debug!("loaded layout '{}' from '{}'", name, path.display());
+                templates.insert(
+                    name,
+                    fs::read_to_string(&path).map_err(|_| Error::FileReadError)?,
+                );
+            }
+        }
+
+        Ok(Self { templates })
+    }
+
+    /// Lays out a document with the layout of the given name, falling back to
+    /// the default layout if it is given none or there is no such layout, and
+    /// filling in the given fields and [`Data`]. Returns [`None`] if there is
+    /// no layout to use, the document's content being kept as it is.
+    ///
+    /// [`Data`]: Data
+    /// [`None`]: None
+    #[must_use]
+    pub fn apply(
+        &self,
+        name: Option<&str>,
+        fields: &[(&str, &str)],
+        data: &Data,
+    ) -> Option<String> {
+        let template = match name {
+            Some(name) => match self.templates.get(name) {
+                Some(template) => Some(template),
+                None => {
+                    log::warn!("there is no layout named '{}', using the default", name);
+                    self.templates.get(DEFAULT_LAYOUT)
+                }
+            },
+            None => self.templates.get(DEFAULT_LAYOUT),
+        };
+
+        template.map(|template| fill(template, fields, data))
+    }
+}
+
+/// Fills in each field, written like `{{ name }}`, of the given template with
+/// the value of the given field of the same name, or with the value at the
+/// path following `data.` in the given [`Data`], e.g. `{{ data.nav.home }}`.
+/// Unknown fields are left as they are.
+///
+/// [`Data`]: Data
+#[must_use]
+pub fn fill(template: &str, fields: &[(&str, &str)], data: &Data) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end + 2,
+            None => break,
+        };
+
+        let name = rest[start + 2..end - 2].trim();
+        filled.push_str(&rest[..start]);
+
+        let value = match fields.iter().find(|(field, _)| *field == name) {
+            Some((_, value)) => Some(value.to_string()),
+            None => name
+                .strip_prefix("data.")
+                .and_then(|path| data.render(path)),
+        };
+
+        filled.push_str(value.as_deref().unwrap_or(&rest[start..end]));
+
+        rest = &rest[end..];
+    }
+
+    filled + rest
+}
+
+/// The result type of layout related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while loading layouts.
+#[derive(Debug)]
+pub enum Error {
+    /// I/O failure to read a layout.
+    FileReadError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_test() {
+        let fields = [("title", "Notes"), ("content", "<p>Hi</p>")];
+        let data = Data::default();
+
+        assert_eq!(
+            fill(
+                "<h1>{{ title }}</h1>{{content}}{{ other }}{{",
+                &fields,
+                &data
+            ),
+            "<h1>Notes</h1><p>Hi</p>{{ other }}{{"
+        );
+
+        let mut layouts = Layouts {
+            templates: HashMap::from([(
+                "project".to_owned(),
+                "<main>{{ content }}</main>".to_owned(),
+            )]),
+        };
+
+        assert_eq!(
+            layouts.apply(Some("project"), &fields, &data).as_deref(),
+            Some("<main><p>Hi</p></main>")
+        );
+        assert_eq!(layouts.apply(Some("missing"), &fields, &data), None);
+        assert_eq!(layouts.apply(None, &fields, &data), None);
+
+        layouts
+            .templates
+            .insert(DEFAULT_LAYOUT.to_owned(), "{{ title }}".to_owned());
+
+        assert_eq!(
+            layouts.apply(Some("missing"), &fields, &data).as_deref(),
+            Some("Notes")
+        );
+        assert_eq!(
+            layouts.apply(None, &fields, &data).as_deref(),
+            Some("Notes")
+        );
+    }
+}
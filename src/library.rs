@@ -2,16 +2,50 @@
 // Licensed under the MIT License.
 // See LICENSE file in repository root for full text.
 
-use crate::href::Href;
-use crate::{fnv1_hash::Hashable, md_content::MdContent};
+use crate::{
+    archive, authors,
+    buffer::ReadBuffer,
+    config::Scan,
+    data::Data,
+    diagnostic::Diagnostic,
+    epub, escape, fingerprint,
+    front_matter::FrontMatter,
+    graph::{self, Graph},
+    hash::{Digest, HashAlgorithm},
+    href,
+    images::{self, Optimized},
+    lang,
+    layout::Layouts,
+    md_content::MdContent,
+    protect,
+    related::{self, Profile},
+    scripts::{self, Scripts},
+    search::{self, SearchEntry},
+    series, share,
+    site::{self, Site},
+    theme,
+    webmention::Mention,
+};
 use build_html as html;
 use glob;
 use html::{Container, Html, HtmlContainer};
 use ron;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, error, ffi, fmt, fs, path::Path, rc::Rc, result};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    error, ffi, fmt, fs, mem,
+    path::{Path, PathBuf},
+    rc::Rc,
+    result,
+};
 use time;
 
+/// Hint given with errors in a library file.
+const LIBRARY_HINT: &str =
+    "the library is written by whim, check any changes made to it by hand or recreate it with `whim new`";
+
 /// Represents a library and holds information about its documents.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Library {
@@ -21,38 +55,63 @@ pub struct Library {
     /// [`HashMap`]: HashMap
     /// [`Document`]: Document
     documents: HashMap<Rc<str>, Document>,
+
+    /// Site wide settings used when generating HTML.
+    #[serde(default)]
+    site: Site,
+
+    /// The algorithm used to hash documents, documents hashed with another
+    /// algorithm are rehashed by [`migrate_hashes`].
+    ///
+    /// [`migrate_hashes`]: Library::migrate_hashes
+    #[serde(default)]
+    hash_algorithm: HashAlgorithm,
+
+    /// The commit checked out when every document was last up to date, if
+    /// git is used, so that only documents changed since need be hashed.
+    #[serde(default)]
+    commit: Option<String>,
 }
 
 impl Library {
-    /// Scans the current directory for any files ending in the ".md" file
-    /// extension and creates a new [`Library`] by opening each file as a
-    /// [`Document`].
+    /// Scans the current directory for markdown files, as given by [`Scan`],
+    /// and creates a new [`Library`] by opening each file as a [`Document`].
     ///
+    /// [`Scan`]: Scan
     /// [`Document`]: Document
     /// [`Library`]: Library
-    pub fn scan() -> Result<Self> {
+    pub fn scan(scan: &Scan) -> Result<Self> {
+        let mut buffer = ReadBuffer::new();
+
         Ok(Self {
-            documents: glob::glob("./**/*.md")?
+            documents: scan
+                .paths()?
+                .into_iter()
                 .filter_map(|path| {
-                    let path = path.ok()?;
-                    let doc = Document::open(&path).ok()?;
-                    Some((path.as_os_str().to_str()?.into(), doc))
+                    let doc = Document::open(&path, HashAlgorithm::default(), &mut buffer).ok()?;
+                    Some((href::from_path(&path).into(), doc))
                 })
                 .collect(),
+            site: Site::default(),
+            hash_algorithm: HashAlgorithm::default(),
+            commit: scan.head(),
         })
     }
 
-    /// Scans the current directory for markdown files and returns a [`Vec`] of
-    /// paths to documents not yet included in the [`Library`].
+    /// Scans the current directory for markdown files, as given by [`Scan`],
+    /// and returns a [`Vec`] of paths to documents not yet included in the
+    /// [`Library`].
     ///
+    /// [`Scan`]: Scan
     /// [`Vec`]: Vec
     /// [`Library`]: Library
-    pub fn scan_for_new(&self) -> Result<Vec<Rc<str>>> {
-        Ok(glob::glob("./**/*.md")?
+    pub fn scan_for_new(&self, scan: &Scan) -> Result<Vec<Rc<str>>> {
+        Ok(scan
+            .paths()?
+            .into_iter()
             .filter_map(|file| {
-                let file = file.ok()?;
-                let path = file.as_os_str().to_str()?;
-                match self.documents.contains_key(path) {
+                let path = href::from_path(&file);
+                match self.documents.contains_key(path.as_str()) {
                     true => None,
                     false => Some(path.into()),
                 }
@@ -61,16 +120,36 @@ impl Library {
     }
 
     /// Reads a serialized [`Library`] from a RON file with the given path.
+    /// The paths of its documents are normalized, since libraries made on
+    /// Windows by older versions may separate them with '\\'. If the file
+    /// cannot be parsed where and why is logged.
     ///
     /// [`Library`]: Library
-    #[inline]
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        ron::from_str(
-            fs::read_to_string(path)
-                .map_err(|_| Error::FileReadError)?
-                .as_str(),
-        )
-        .map_err(|_| Error::DeserializationError)
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|_| Error::FileReadError)?;
+
+        let lib: Self = ron::from_str(&source).map_err(|e| {
+            let diagnostic = Diagnostic::new(
+                path,
+                &source,
+                e.position.line,
+                e.position.col,
+                e.code.to_string(),
+            );
+
+            log::error!("{}", diagnostic.with_hint(LIBRARY_HINT));
+            Error::DeserializationError
+        })?;
+
+        Ok(Self {
+            documents: lib
+                .documents
+                .into_iter()
+                .map(|(p, d)| (href::from_path(Path::new(&*p)).into(), d))
+                .collect(),
+            ..lib
+        })
     }
 
     /// Saves the [`Library`], in RON format, to the given file path.
@@ -86,17 +165,26 @@ impl Library {
         .map_err(|_| Error::FileWriteError)
     }
 
-    /// Opens a [`Document`] at the given path and adds it to the [`Library`].
+    /// Opens a [`Document`] at the given path and adds it to the [`Library`],
+    /// keyed by the normalized path so that "./a/../b.md" and "b.md" are the
+    /// same document.
     ///
     /// [`Document`]: Document
     /// [`Library`]: Library
     pub fn add_document(&mut self, path: impl AsRef<Path>) -> Result<()> {
-        let doc = Document::open(&path)?;
+        let doc = Document::open(&path, self.hash_algorithm, &mut ReadBuffer::new())?;
         let path = match path.as_ref().as_os_str().to_str() {
-            Some(s) => Ok(s.into()),
+            Some(s) => Ok(href::from_path(Path::new(s)).into()),
             None => Err(Error::InvalidString),
         }?;
 
+        log::info!("added '{}'", path);
+        log::debug!(
+            "hashed '{}' with {:?}: {}",
+            path,
+            self.hash_algorithm,
+            doc.hash
+        );
         self.documents.insert(path, doc);
         Ok(())
     }
@@ -114,128 +202,1077 @@ impl Library {
         &self.documents
     }
 
-    /// Updates all [`Document`] items within the [`Library`].
-    //
+    /// Gets the [`Site`] settings of the [`Library`].
+    ///
+    /// [`Site`]: Site
+    /// [`Library`]: Library
+    #[inline]
+    #[must_use]
+    pub fn site(&self) -> &Site {
+        &self.site
+    }
+
+    /// Gets a mutable reference to the [`Site`] settings of the [`Library`].
+    ///
+    /// [`Site`]: Site
+    /// [`Library`]: Library
+    #[inline]
+    #[must_use]
+    pub fn site_mut(&mut self) -> &mut Site {
+        &mut self.site
+    }
+
+    /// Gets the commit checked out when every [`Document`] in the [`Library`]
+    /// was last up to date, if known.
+    ///
     /// [`Document`]: Document
     /// [`Library`]: Library
-    pub fn update(self) -> Result<Self> {
+    #[inline]
+    #[must_use]
+    pub fn commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    /// Sets the commit checked out when every [`Document`] in the [`Library`]
+    /// was last up to date, [`None`] if unknown.
+    ///
+    /// [`Document`]: Document
+    /// [`Library`]: Library
+    /// [`None`]: None
+    #[inline]
+    pub fn set_commit(&mut self, commit: Option<String>) {
+        self.commit = commit;
+    }
+
+    /// Updates only the [`Document`] items at the given paths within the
+    /// [`Library`], leaving all others as they are.
+    ///
+    /// [`Document`]: Document
+    /// [`Library`]: Library
+    pub fn update_documents(self, paths: &[impl AsRef<str>]) -> Result<Self> {
+        let mut buffer = ReadBuffer::new();
+
         Ok(Self {
             documents: self
                 .documents
                 .into_iter()
                 .map(|(p, d)| -> Result<(Rc<str>, Document)> {
-                    let s = &*p;
-                    let doc = d.update(&s)?;
-                    Ok((p, doc))
+                    match paths.iter().any(|path| path.as_ref() == &*p) {
+                        true => {
+                            let doc = d.update(&*p, self.hash_algorithm, &mut buffer)?;
+                            log::info!("updated '{}'", p);
+                            log::debug!(
+                                "hashed '{}' with {:?}: {}",
+                                p,
+                                doc.hash_algorithm,
+                                doc.hash
+                            );
+                            Ok((p, doc))
+                        }
+                        false => Ok((p, d)),
+                    }
                 })
                 .filter_map(result::Result::ok)
                 .collect(),
+            ..self
         })
     }
 
+    /// Rehashes every [`Document`] hashed with an algorithm other than the
+    /// [`Library`]'s, returning the number rehashed. Documents that have
+    /// changed since their last update are left as they are, so that they are
+    /// still seen as changed, and are rehashed when updated.
+    ///
+    /// [`Document`]: Document
+    /// [`Library`]: Library
+    pub fn migrate_hashes(&mut self) -> usize {
+        let algorithm = self.hash_algorithm;
+        let mut buffer = ReadBuffer::new();
+
+        self.documents
+            .iter_mut()
+            .map(|(p, d)| {
+                let migrated = d
+                    .migrate(p.as_ref(), algorithm, &mut buffer)
+                    .unwrap_or(false);
+
+                if migrated {
+                    log::debug!("rehashed '{}' with {:?}: {}", p, algorithm, d.hash);
+                }
+
+                migrated
+            })
+            .filter(|&migrated| migrated)
+            .count()
+    }
+
+    /// Merges the documents of another [`Library`] into this one, as when the
+    /// same documents have been worked on from two machines. Documents in only
+    /// one library are kept, and of a document in both the newest version is
+    /// kept, though with the earliest creation time of the two. Every document
+    /// whose versions differ is returned as a [`Conflict`]. The [`Site`]
+    /// settings and hash algorithm of this library are kept, so documents from
+    /// the other library may be rehashed by [`migrate_hashes`]. The other
+    /// library's documents may be out of date at any commit, so the commit of
+    /// the last update is forgotten.
+    ///
+    /// [`Library`]: Library
+    /// [`Conflict`]: Conflict
+    /// [`Site`]: Site
+    /// [`migrate_hashes`]: Library::migrate_hashes
+    pub fn merge(&mut self, other: Library) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        self.commit = None;
+
+        for (path, theirs) in other.documents {
+            let ours = match self.documents.get_mut(&path) {
+                Some(ours) => ours,
+                None => {
+                    log::info!("merged '{}'", path);
+                    self.documents.insert(path, theirs);
+                    continue;
+                }
+            };
+
+            let create_time = ours.create_time.min(theirs.create_time);
+            let same = ours.hash_algorithm == theirs.hash_algorithm && ours.hash == theirs.hash;
+            let kept = match theirs.mod_time > ours.mod_time {
+                true => {
+                    *ours = theirs;
+                    Kept::Theirs
+                }
+                false => Kept::Ours,
+            };
+
+            ours.create_time = create_time;
+
+            if !same {
+                log::info!("'{}' differs between libraries, kept {:?}", path, kept);
+                conflicts.push(Conflict { path, kept });
+            }
+        }
+
+        conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+        conflicts
+    }
+
+    /// Gets the algorithm used to hash documents in the [`Library`].
+    ///
+    /// [`Library`]: Library
+    #[inline]
+    #[must_use]
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.hash_algorithm
+    }
+
     /// Checks each of this [`Library`]'s documents for change since last update
     /// and returns a [`Vec`] containing the paths of those [`Document`]s. This
-    /// function does not propagate I/O errors from reading documents.
+    /// function does not propagate I/O errors from reading documents. If the
+    /// given [`Scan`] uses git and the commit of the last update is known,
+    /// only documents git reports as changed since that commit are checked.
+    /// Otherwise every document is.
     ///
     /// [`Library`]: Library
     /// [`Vec`]: Vec
     /// [`Document`]: Document
-    pub fn changed_docs(&self) -> Vec<&str> {
-        self.documents
+    /// [`Scan`]: Scan
+    pub fn changed_docs(&self, scan: &Scan) -> Vec<&str> {
+        let candidates = scan.changed(self.commit()).map(|paths| {
+            paths
+                .iter()
+                .map(|path| href::from_path(path))
+                .collect::<HashSet<_>>()
+        });
+
+        let mut by_algorithm: HashMap<HashAlgorithm, Vec<&str>> = HashMap::new();
+
+        for (p, d) in &self.documents {
+            if candidates.as_ref().is_some_and(|c| !c.contains(&**p)) {
+                continue;
+            }
+
+            by_algorithm.entry(d.hash_algorithm).or_default().push(p);
+        }
+
+        by_algorithm
+            .into_iter()
+            .flat_map(|(algorithm, paths)| {
+                let hashes = algorithm.hash_files(&paths);
+
+                paths
+                    .into_iter()
+                    .filter(move |p| match hashes.get(Path::new(p)) {
+                        Some(hash) if *hash != self.documents[*p].hash => {
+                            log::debug!(
+                                "'{}' changed, its hash {} is now {}",
+                                p,
+                                self.documents[*p].hash,
+                                hash
+                            );
+                            true
+                        }
+                        _ => false,
+                    })
+            })
+            .collect()
+    }
+
+    /// Creates a [`Mention`] of every external link in the documents at the
+    /// given paths, by their page's URL and with their current hash. Documents
+    /// are only read when the site has a base URL, since a mention's source
+    /// must be a full URL, and those which cannot be read are skipped.
+    /// Protected documents are skipped too, as mentions would give away their
+    /// links.
+    ///
+    /// [`Mention`]: Mention
+    #[must_use]
+    pub fn mentions(&self, paths: &[&str]) -> Vec<Mention> {
+        let base_url = match self.site.base_url() {
+            Some(url) => url,
+            None => return Vec::new(),
+        };
+
+        let mut buffer = ReadBuffer::new();
+
+        paths
             .iter()
-            .filter_map(|(p, d)| match d.has_changed(&p.as_ref()).ok()? {
-                true => Some(p.as_ref()),
-                false => None,
+            .filter_map(|path| {
+                let source = self.site.url(&self.doc_href(path))?;
+                let text = buffer.read_str(path).ok()?;
+
+                if FrontMatter::parse(path, &text).ok()?.protect().is_some() {
+                    log::debug!("not mentioning links of protected '{}'", path);
+                    return None;
+                }
+
+                let hash = self.hash_algorithm.hash(text.as_bytes());
+                let md = MdContent::new(text);
+
+                Some(
+                    md.links()
+                        .into_iter()
+                        .filter(|link| link.starts_with("http://") || link.starts_with("https://"))
+                        .filter(|link| !link.starts_with(base_url))
+                        .map(move |target| Mention {
+                            source: source.clone(),
+                            target,
+                            hash,
+                        }),
+                )
             })
+            .flatten()
             .collect()
     }
 
     /// Creates and returns a [`LibraryHtml`] from documents managed by this
-    /// [`Library`].
+    /// [`Library`], the page of each document being transformed by the given
+    /// [`Scripts`] and then laid out with the given [`Layouts`], both of which
+    /// are given the site's [`Data`].
     ///
     /// [`Library`]: Library
     /// [`LibraryHtml`]: LibraryHtml
-    pub fn gen_html(&self) -> Result<LibraryHtml> {
-        let mut pages: Vec<(String, html::HtmlPage)> = self
+    /// [`Scripts`]: Scripts
+    /// [`Layouts`]: Layouts
+    /// [`Data`]: Data
+    pub fn gen_html(
+        &self,
+        scripts: &Scripts,
+        layouts: &Layouts,
+        data: &Data,
+    ) -> Result<LibraryHtml> {
+        let languages = self.site.languages();
+
+        if !self.site.share().is_empty() && self.site.base_url().is_none() {
+            log::warn!("share links need the site's base URL to be set, pages will have none");
+        }
+
+        // Hrefs of every translation of a document, keyed by the path shared
+        // between them.
+        let mut translations: HashMap<String, Vec<(&str, String)>> = HashMap::new();
+
+        for p in self.documents.keys() {
+            if let Some((lang, shared)) = lang::split(p, languages) {
+                translations
+                    .entry(shared)
+                    .or_default()
+                    .push((lang, self.doc_href(p)));
+            }
+        }
+
+        for hrefs in translations.values_mut() {
+            hrefs.sort_by_key(|(lang, _)| languages.iter().position(|l| **l == **lang));
+        }
+
+        // Every document is read up front, as pages link to related ones.
+        let mut buffer = ReadBuffer::new();
+        let sources = self
             .documents
+            .keys()
+            .map(|p| -> Result<(&str, (FrontMatter, MdContent))> {
+                let source = read_markdown(p.as_ref(), &mut buffer)
+                    .inspect_err(|_| log::error!("could not read '{}'", p))?;
+                let front_matter =
+                    FrontMatter::parse(p.as_ref(), &source).map_err(|_| Error::FrontMatterError)?;
+                Ok((p.as_ref(), (front_matter, MdContent::new(&source))))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        // Protected documents are left out of anything built from their
+        // content outside of their own page, which would give it away.
+        let profiles = sources
             .iter()
-            .map(|(p, doc)| -> Result<(String, html::HtmlPage)> {
-                let href = p.replace(".md", ".html");
-                let md = MdContent::new(
-                    fs::read_to_string(&p.as_ref()).map_err(|_| Error::FileReadError)?,
-                );
-
-                let title = match md.title() {
-                    Some(cow_str) => cow_str.as_ref().to_owned(),
-                    None => "".to_owned(),
-                };
-
-                Ok((
-                    href,
-                    html::HtmlPage::new()
-                        .with_title(title)
-                        .with_stylesheet("styles.css")
-                        .with_link(
-                            "../".to_owned().repeat(p.clone().path_items() - 1) + "index.html",
-                            "HOME",
-                        )
-                        .with_container(
-                            Container::new(html::ContainerType::Div)
-                                .with_attributes(vec![("class", "content")])
-                                .with_html(md),
-                        )
-                        .with_paragraph(format!(
-                            "Created: {} {} {}, {}",
-                            doc.create_time.day(),
-                            doc.create_time.month(),
-                            doc.create_time.year(),
-                            match doc.create_time.hour() {
-                                hour @ 1..=12 =>
-                                    format!("{}:{:0>2} AM", hour, doc.create_time.minute()),
-                                hour @ 13..=24 =>
-                                    format!("{}:{:0>2} PM", hour - 12, doc.create_time.minute()),
-                                0 => format!("12:{:0>2} PM", doc.create_time.minute()),
-                                _ => unreachable!(),
-                            },
-                        ))
-                        .with_paragraph(format!(
-                            "Last Modified: {} {} {}, {}",
-                            doc.mod_time.day(),
-                            doc.mod_time.month(),
-                            doc.mod_time.year(),
-                            match doc.create_time.hour() {
-                                hour @ 1..=12 =>
-                                    format!("{}:{:0>2} AM", hour, doc.mod_time.minute()),
-                                hour @ 13..=24 =>
-                                    format!("{}:{:0>2} PM", hour - 12, doc.mod_time.minute()),
-                                0 => format!("12:{:0>2} PM", doc.mod_time.minute()),
-                                _ => unreachable!(),
-                            },
-                        )),
-                ))
+            .filter(|(_, (front_matter, _))| front_matter.protect().is_none())
+            .map(|(p, (front_matter, md))| {
+                let title = md.title().map(|t| t.to_string()).unwrap_or_default();
+                (
+                    *p,
+                    Profile::new(p, &title, front_matter.tags(), &md.links()),
+                )
             })
-            .filter_map(result::Result::ok)
-            .collect::<Vec<_>>();
+            .collect::<BTreeMap<_, _>>();
+
+        let series = series::collect(sources.iter().filter_map(|(p, (front_matter, _))| {
+            Some(series::Member {
+                series: front_matter.series()?,
+                part: front_matter.part(),
+                date: self.site.archive_by().of(&self.documents[*p]),
+                key: p,
+            })
+        }));
+
+        // Authors are only given pages if the library has several.
+        let mut authors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
 
-        if pages.len() != self.documents.len() {
-            // At least one item was filtered out and an error must have occured.
-            return Err(Error::FileReadError);
+        for (p, (front_matter, _)) in &sources {
+            if let Some(author) = front_matter.author().or(self.site.author()) {
+                authors.entry(author).or_default().push(p);
+            }
         }
 
-        let list = self.documents.iter().fold(
-            html::Container::new(html::ContainerType::UnorderedList),
-            |acc, (p, d)| acc.with_link(p.replace(".md", ".html"), d.name()),
+        // Pages given the same href would silently overwrite each other.
+        let collisions = href::collisions(
+            self.documents
+                .keys()
+                .map(|p| (self.doc_href(p), p.as_ref()))
+                .chain(series.keys().map(|name| (series::href(name), *name)))
+                .chain(
+                    authors
+                        .keys()
+                        .filter(|_| authors.len() > 1)
+                        .map(|name| (authors::href(name), *name)),
+                ),
         );
 
+        for (href, sources) in &collisions {
+            log::error!(
+                "'{}' would all be written to '{}', rename all but one",
+                sources.join("', '"),
+                href
+            );
+        }
+
+        if !collisions.is_empty() {
+            return Err(Error::HrefCollisionError);
+        }
+
+        // Images are optimized up front, as documents may share them. Those of
+        // protected documents are not, as they would be written unencrypted.
+        let mut optimized: BTreeMap<String, Optimized> = BTreeMap::new();
+
+        if let Some(settings) = self.site.images() {
+            let unprotected = sources
+                .iter()
+                .filter(|(_, (front_matter, _))| front_matter.protect().is_none());
+
+            for (p, (_, md)) in unprotected {
+                for href in md.images().iter().filter_map(|src| images::resolve(p, src)) {
+                    if optimized.contains_key(&href) {
+                        continue;
+                    }
+
+                    if !Path::new(&href).is_file() {
+                        log::warn!("'{}' has an image '{}' which does not exist", p, href);
+                        continue;
+                    }
+
+                    let image = settings
+                        .optimize(Path::new(&href), Path::new(images::CACHE_DIR))
+                        .inspect_err(|_| log::error!("could not optimize '{}'", href))
+                        .map_err(|_| Error::ImageError)?;
+                    optimized.insert(href, image);
+                }
+            }
+        }
+
+        // Hrefs of each image's WebP variant, keyed by the image's href.
+        let variants = optimized
+            .iter()
+            .filter(|(_, image)| image.webp.is_some())
+            .map(|(href, _)| {
+                (
+                    href.clone(),
+                    href::from_path(&Path::new(href).with_extension("webp")),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let (mut pages, entries): (Vec<(String, html::HtmlPage)>, Vec<Option<SearchEntry>>) = self
+            .documents
+            .iter()
+            .map(
+                |(p, doc)| -> Result<((String, html::HtmlPage), Option<SearchEntry>)> {
+                    let href = self.doc_href(p);
+                    log::debug!("generating '{}' from '{}'", href, p);
+
+                    // Every document was read above.
+                    let (front_matter, md) = &sources[p.as_ref()];
+
+                    let split = lang::split(p, languages);
+                    let alternates = split
+                        .as_ref()
+                        .and_then(|(_, shared)| translations.get(shared))
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+
+                    // Protected documents are left out of the search index,
+                    // which would otherwise give away their content.
+                    let entry = match front_matter.protect() {
+                        Some(_) => None,
+                        None => Some(SearchEntry::new(
+                            md,
+                            self.site.href(search::PAGE_HREF, &href),
+                            self.site.search_body(),
+                        )),
+                    };
+
+                    let content = match front_matter.sidenotes().unwrap_or(self.site.sidenotes()) {
+                        true => md.to_html_with_sidenotes(),
+                        false => md.to_html_string(),
+                    };
+
+                    let mut page = scripts
+                        .transform(scripts::Page {
+                            path: p.to_string(),
+                            href: href.clone(),
+                            title: md.title().map(|t| t.to_string()).unwrap_or_default(),
+                            lang: split.map(|(lang, _)| lang.to_owned()),
+                            content,
+                        })
+                        .map_err(|_| Error::ScriptError)?;
+
+                    if !variants.is_empty() {
+                        page.content = images::pictures(&page.content, p, &variants);
+                    }
+
+                    let (created, updated) = (
+                        self.site.date_html(doc.create_time),
+                        self.site.date_html(doc.mod_time),
+                    );
+
+                    // Every field but the title and href is already HTML.
+                    if let Some(content) = layouts.apply(
+                        front_matter.layout(),
+                        &[
+                            ("title", &escape::html(&page.title)),
+                            ("content", &page.content),
+                            ("href", &escape::html(&page.href)),
+                            ("created", &created),
+                            ("updated", &updated),
+                        ],
+                        data,
+                    ) {
+                        page.content = content;
+                    }
+
+                    if let Some(passphrase) = front_matter.protect() {
+                        let locked = protect::encrypt(&page.content, passphrase)
+                            .inspect_err(|_| log::error!("could not encrypt '{}'", p))
+                            .map_err(|_| Error::EncryptionError)?;
+                        page.content =
+                            protect::content(&locked, &self.site.href(&href, protect::SCRIPT_HREF));
+                    }
+
+                    let related = related::related(&profiles, p, self.site.related())
+                        .into_iter()
+                        .map(|r| {
+                            (
+                                self.site.href(&href, &self.doc_href(r)),
+                                self.documents[r].name(),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    let series_nav = front_matter.series().map(|name| {
+                        let parts = series[name]
+                            .iter()
+                            .map(|k| {
+                                (
+                                    self.site.href(&href, &self.doc_href(k)),
+                                    self.documents[*k].name(),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        let position = series[name]
+                            .iter()
+                            .position(|k| *k == p.as_ref())
+                            .unwrap_or_default();
+
+                        series::navigation(
+                            name,
+                            &self.site.href(&href, &series::href(name)),
+                            &parts,
+                            position,
+                        )
+                    });
+
+                    let author = front_matter.author().or(self.site.author()).map(|name| {
+                        let page = match authors.len() > 1 {
+                            true => Some(self.site.href(&href, &authors::href(name))),
+                            false => None,
+                        };
+
+                        (name, page)
+                    });
+
+                    let links = DocumentLinks {
+                        alternates,
+                        series: series_nav,
+                        related,
+                        author,
+                    };
+
+                    let html = self.document_page(&page, doc, front_matter, links);
+                    Ok(((href, html), entry))
+                },
+            )
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        let title = self.site.title();
+
         pages.push((
             "index.html".to_owned(),
-            html::HtmlPage::new()
-                .with_title("HOME")
-                .with_header(1, "HOME")
-                .with_container(list),
+            self.page(
+                "index.html",
+                &title,
+                None,
+                &[],
+                self.index_content(&title, "index.html", self.documents.iter()),
+            ),
         ));
 
-        Ok(LibraryHtml::new(pages))
+        let indexes: Vec<(&str, String)> = languages
+            .iter()
+            .map(|lang| (lang.as_ref(), format!("{}/index.html", lang)))
+            .collect();
+
+        for (lang, href) in &indexes {
+            let docs = self
+                .documents
+                .iter()
+                .filter(|(p, _)| lang::split(p, languages).map(|(l, _)| l) == Some(*lang));
+
+            pages.push((
+                href.clone(),
+                self.page(
+                    href,
+                    &title,
+                    Some(lang),
+                    &indexes,
+                    self.index_content(&title, href, docs),
+                ),
+            ));
+        }
+
+        pages.push((
+            archive::PAGE_HREF.to_owned(),
+            self.page(
+                archive::PAGE_HREF,
+                "Archive",
+                None,
+                &[],
+                archive::content(
+                    self.documents
+                        .iter()
+                        .map(|(p, d)| (self.site.href(archive::PAGE_HREF, &self.doc_href(p)), d)),
+                    self.site.archive_by(),
+                ),
+            ),
+        ));
+
+        pages.push((
+            search::PAGE_HREF.to_owned(),
+            self.page(search::PAGE_HREF, "Search", None, &[], search::content()),
+        ));
+
+        for (name, keys) in &series {
+            let index = series::href(name);
+            let docs = keys.iter().map(|k| {
+                (
+                    self.site.href(&index, &self.doc_href(k)),
+                    self.documents[*k].name(),
+                )
+            });
+
+            pages.push((
+                index.clone(),
+                self.page(&index, name, None, &[], series::content(name, docs)),
+            ));
+        }
+
+        if authors.len() > 1 {
+            for (name, mut keys) in authors {
+                let href = authors::href(name);
+                keys.sort_by_key(|k| Reverse(self.site.archive_by().of(&self.documents[*k])));
+
+                let docs = keys.iter().map(|k| {
+                    (
+                        self.site.href(&href, &self.doc_href(k)),
+                        self.documents[*k].name(),
+                    )
+                });
+
+                pages.push((
+                    href.clone(),
+                    self.page(&href, name, None, &[], authors::content(name, docs)),
+                ));
+            }
+        }
+
+        if self.site.graph() {
+            let docs = sources
+                .iter()
+                .filter(|(_, (front_matter, _))| front_matter.protect().is_none())
+                .map(|(p, (_, md))| graph::Document {
+                    path: p,
+                    href: self.doc_href(p),
+                    title: self.documents[*p].name(),
+                    links: md.links(),
+                })
+                .collect::<Vec<_>>();
+
+            let graph = Graph::new(&docs, |href| self.site.href(graph::PAGE_HREF, href));
+
+            pages.push((
+                graph::PAGE_HREF.to_owned(),
+                self.page(graph::PAGE_HREF, "Graph", None, &[], graph::content(&graph)),
+            ));
+        }
+
+        let files = [self.site.favicon(), self.site.touch_icon()]
+            .into_iter()
+            .flatten()
+            .filter_map(|path| Some((site::output_name(path)?, PathBuf::from(path))))
+            .chain(optimized.into_iter().flat_map(|(href, image)| {
+                let variant = image.webp.map(|webp| (variants[&href].clone(), webp));
+                [Some((href, image.path)), variant].into_iter().flatten()
+            }))
+            .collect();
+
+        log::info!("generated {} pages", pages.len());
+
+        let html = LibraryHtml::new(pages)
+            .with_lang(self.site.lang())
+            .with_languages(languages)
+            .with_files(files)
+            .with_output(theme::STYLESHEET_HREF, theme::STYLESHEET)
+            .with_output(search::SCRIPT_HREF, search::SCRIPT)
+            .with_output(
+                search::INDEX_HREF,
+                search::index_json(&entries.into_iter().flatten().collect::<Vec<_>>()),
+            );
+
+        let html = match sources
+            .values()
+            .any(|(front_matter, _)| front_matter.protect().is_some())
+        {
+            true => html.with_output(protect::SCRIPT_HREF, protect::SCRIPT),
+            false => html,
+        };
+
+        let html = match self.site.graph() {
+            true => html.with_output(graph::SCRIPT_HREF, graph::SCRIPT),
+            false => html,
+        };
+
+        Ok(match self.site.theme_toggle() {
+            true => html.with_output(theme::TOGGLE_SCRIPT_HREF, theme::TOGGLE_SCRIPT),
+            false => html,
+        })
+    }
+
+    /// Gets whether the document at the given path is protected by a
+    /// passphrase in its front matter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document could not be read or its front matter
+    /// could not be parsed.
+    pub fn is_protected(&self, path: &str) -> Result<bool> {
+        let mut buffer = ReadBuffer::new();
+        let source = read_markdown(path, &mut buffer)?;
+        let front_matter =
+            FrontMatter::parse(path, &source).map_err(|_| Error::FrontMatterError)?;
+        Ok(front_matter.protect().is_some())
+    }
+
+    /// Gets the href, relative to the root of the output directory, that the
+    /// document at the given path is written to. Documents are written to the
+    /// directory of their language if the site has multiple languages, and
+    /// their hrefs are slugified if the site is set to.
+    #[must_use]
+    pub fn doc_href(&self, path: &str) -> String {
+        let path = href::from_path(Path::new(path));
+
+        let href = match lang::split(&path, self.site.languages()) {
+            Some((lang, shared)) => format!("{}/{}", lang, shared.replace(".md", ".html")),
+            None => path.replace(".md", ".html"),
+        };
+
+        match self.site.slug_hrefs() {
+            true => href::slugify_href(&href),
+            false => href,
+        }
+    }
+
+    /// Creates an EPUB [`Book`] of the documents at the given paths, or of every
+    /// document if none are given. Chapters are ordered by directory, and then
+    /// by the date the site archives documents by. Protected documents are
+    /// left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a document could not be read, its front matter
+    /// could not be parsed, or a script failed.
+    ///
+    /// [`Book`]: epub::Book
+    pub fn gen_epub(&self, paths: &[&str], scripts: &Scripts) -> Result<epub::Book> {
+        let by = self.site.archive_by();
+        let mut docs = self
+            .documents
+            .iter()
+            .filter(|(p, _)| paths.is_empty() || paths.contains(&p.as_ref()))
+            .collect::<Vec<_>>();
+
+        docs.sort_by(|(a, a_doc), (b, b_doc)| {
+            let (a_dir, b_dir) = (
+                Path::new(a.as_ref()).parent(),
+                Path::new(b.as_ref()).parent(),
+            );
+            (a_dir, by.of(a_doc), a).cmp(&(b_dir, by.of(b_doc), b))
+        });
+
+        let title = self.site.title();
+        let identifier = match self.site.base_url() {
+            Some(url) => url.to_owned(),
+            None => format!("urn:whim:{}", href::slugify(&title)),
+        };
+        let modified = docs
+            .iter()
+            .map(|(_, doc)| doc.mod_time())
+            .max()
+            .unwrap_or_else(now);
+
+        let book = epub::Book::new(&title, self.site.lang(), identifier, modified)
+            .with_stylesheet(theme::STYLESHEET);
+
+        let book = match self.site.author() {
+            Some(author) => book.with_author(author),
+            None => book,
+        };
+
+        let mut buffer = ReadBuffer::new();
+
+        docs.into_iter().try_fold(book, |book, (p, doc)| {
+            let href = self.doc_href(p);
+            log::debug!("adding '{}' to the book as '{}'", p, href);
+
+            let source = read_markdown(p.as_ref(), &mut buffer)
+                .inspect_err(|_| log::error!("could not read '{}'", p))?;
+
+            // A book cannot ask for a passphrase, so protected documents are
+            // left out rather than given in the clear.
+            let front_matter =
+                FrontMatter::parse(p.as_ref(), &source).map_err(|_| Error::FrontMatterError)?;
+
+            if front_matter.protect().is_some() {
+                log::warn!("leaving protected '{}' out of the book", p);
+                return Ok(book);
+            }
+
+            let md = MdContent::new(&source);
+
+            let page = scripts
+                .transform(scripts::Page {
+                    path: p.to_string(),
+                    href: href.clone(),
+                    title: md.title().map(|t| t.to_string()).unwrap_or_default(),
+                    lang: lang::split(p, self.site.languages()).map(|(lang, _)| lang.to_owned()),
+                    content: md.to_html_string(),
+                })
+                .map_err(|_| Error::ScriptError)?;
+
+            let title = match page.title.is_empty() {
+                true => doc.name().to_owned(),
+                false => page.title,
+            };
+
+            Ok(book.with_chapter(epub::Chapter {
+                href,
+                title,
+                content: page.content,
+            }))
+        })
+    }
+
+    /// Creates the content of an index page listing the given documents, where
+    /// `from` is the href of the index. The most recently updated documents are
+    /// listed on their own first if there are more documents than are listed.
+    fn index_content<'a>(
+        &self,
+        title: &str,
+        from: &str,
+        docs: impl Iterator<Item = (&'a Rc<str>, &'a Document)>,
+    ) -> Container {
+        let docs = docs.collect::<Vec<_>>();
+        let link = |p: &str, d: &Document| {
+            format!(
+                "<a href=\"{}\">{}</a>",
+                self.site.href(from, &self.doc_href(p)),
+                escape::html(d.name())
+            )
+        };
+
+        let list = docs.iter().fold(
+            "<table><thead><tr><th>Document</th><th>Last Updated</th></tr></thead><tbody>"
+                .to_owned(),
+            |acc, (p, d)| {
+                acc + format!(
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    link(p, d),
+                    self.site.date_html(d.mod_time)
+                )
+                .as_str()
+            },
+        ) + "</tbody></table>";
+
+        let content = Container::new(html::ContainerType::Div).with_header(1, escape::html(title));
+        let count = self.site.recent();
+
+        // Listing every document as recently updated would only repeat them.
+        let content = match docs.len() > count && count > 0 {
+            true => {
+                let mut recent = docs.clone();
+                recent.sort_by_key(|(_, d)| Reverse(d.mod_time));
+
+                content.with_container(
+                    recent.into_iter().take(count).fold(
+                        Container::new(html::ContainerType::Div)
+                            .with_attributes(vec![("class", "recent")])
+                            .with_header(2, "Recently updated"),
+                        |recent, (p, d)| {
+                            recent.with_paragraph(format!(
+                                "{} {}",
+                                link(p, d),
+                                self.site.date_html(d.mod_time)
+                            ))
+                        },
+                    ),
+                )
+            }
+            false => content,
+        };
+
+        content.with_raw(list)
+    }
+
+    /// Creates the [`HtmlPage`] of a single [`Document`] from its [`Page`] and
+    /// its links to other pages. The site's comments are embedded unless the
+    /// document's [`FrontMatter`] opts out.
+    ///
+    /// [`FrontMatter`]: FrontMatter
+    /// [`HtmlPage`]: html::HtmlPage
+    /// [`Document`]: Document
+    /// [`Page`]: scripts::Page
+    fn document_page(
+        &self,
+        page: &scripts::Page,
+        doc: &Document,
+        front_matter: &FrontMatter,
+        links: DocumentLinks,
+    ) -> html::HtmlPage {
+        let content =
+            Container::new(html::ContainerType::Div).with_attributes(vec![("class", "content")]);
+
+        let content = match links.series {
+            Some(series) => content.with_container(series),
+            None => content,
+        }
+        .with_raw(&page.content);
+
+        let title = match page.title.is_empty() {
+            true => doc.name(),
+            false => &page.title,
+        };
+
+        let content = match self.site.url(&page.href) {
+            Some(url) if !self.site.share().is_empty() => {
+                content.with_container(share::content(self.site.share(), &url, title))
+            }
+            _ => content,
+        };
+
+        let content = match links.related.is_empty() {
+            true => content,
+            false => content.with_container(related::content(links.related)),
+        };
+
+        let content = match (self.site.comments(), front_matter.comments()) {
+            (Some(comments), None | Some(true)) => content.with_raw(comments.html()),
+            _ => content,
+        };
+
+        let html = self.page(
+            &page.href,
+            &page.title,
+            page.lang.as_deref(),
+            links.alternates,
+            content,
+        );
+
+        let (html, footer) = match links.author {
+            Some((name, href)) => (
+                html.with_meta(vec![("name", "author"), ("content", &escape::html(name))]),
+                Container::new(html::ContainerType::Footer).with_paragraph_attr(
+                    authors::byline(name, href.as_deref()),
+                    vec![("class", "byline")],
+                ),
+            ),
+            None => (html, Container::new(html::ContainerType::Footer)),
+        };
+
+        html.with_container(
+            footer
+                .with_paragraph(format!("Created: {}", self.site.date_html(doc.create_time)))
+                .with_paragraph_attr(
+                    format!("Last updated: {}", self.site.date_html(doc.mod_time)),
+                    vec![("class", "last-updated")],
+                ),
+        )
+    }
+
+    /// Creates an [`HtmlPage`] with the given title, a header holding the
+    /// site's navigation links, and the given content as its main content.
+    /// `from` is the href of the page, which links are made relative to.
+    ///
+    /// If the page is in one of the site's languages then `lang` is that
+    /// language and its home link leads to the language's index. `alternates`
+    /// are the language and href of each translation of the page, which are
+    /// linked to from the page's head and a language switcher. Pages link to
+    /// their canonical URL if the site has a base URL.
+    ///
+    /// [`HtmlPage`]: html::HtmlPage
+    fn page(
+        &self,
+        from: &str,
+        title: &str,
+        lang: Option<&str>,
+        alternates: &[(&str, String)],
+        content: impl html::Html,
+    ) -> html::HtmlPage {
+        let home = match lang {
+            Some(lang) => format!("{}/index.html", lang),
+            None => "index.html".to_owned(),
+        };
+
+        let links = [
+            (home.as_str(), self.site.home_label()),
+            (search::PAGE_HREF, "Search"),
+            (archive::PAGE_HREF, "Archive"),
+        ];
+
+        let nav = links.into_iter().fold(
+            Container::new(html::ContainerType::Nav).with_attributes(vec![("aria-label", "Site")]),
+            |nav, (href, label)| nav.with_link(self.site.href(from, href), label),
+        );
+
+        let header = match alternates.len() > 1 {
+            true => Container::new(html::ContainerType::Header)
+                .with_container(nav)
+                .with_container(
+                    alternates.iter().fold(
+                        Container::new(html::ContainerType::Nav)
+                            .with_attributes(vec![("aria-label", "Languages")]),
+                        |nav, (l, href)| match Some(*l) == lang {
+                            true => nav.with_link_attr(
+                                self.site.href(from, href),
+                                l,
+                                vec![("hreflang", *l), ("aria-current", "page")],
+                            ),
+                            false => nav.with_link_attr(
+                                self.site.href(from, href),
+                                l,
+                                vec![("hreflang", *l)],
+                            ),
+                        },
+                    ),
+                ),
+            false => Container::new(html::ContainerType::Header).with_container(nav),
+        };
+
+        let page = match self.site.url(from) {
+            Some(url) => self.base_page(from).with_head_link(url, "canonical"),
+            None => self.base_page(from),
+        };
+
+        alternates
+            .iter()
+            .fold(page, |page, (l, href)| {
+                page.with_head_link_attr(
+                    self.site.href(from, href),
+                    "alternate",
+                    vec![("hreflang", *l)],
+                )
+            })
+            .with_title(escape::html(title))
+            .with_container(header)
+            .with_container(
+                Container::new(html::ContainerType::Main)
+                    .with_attributes(vec![("id", "content")])
+                    .with_html(content),
+            )
+    }
+
+    /// Creates an [`HtmlPage`] with the stylesheet, the [`Site`]'s icons and
+    /// mention endpoints, a skip to content link, and the theme toggle if
+    /// enabled, where `from` is the href of the page.
+    ///
+    /// [`Site`]: Site
+    /// [`HtmlPage`]: html::HtmlPage
+    fn base_page(&self, from: &str) -> html::HtmlPage {
+        let page = self
+            .site
+            .icons()
+            .into_iter()
+            .fold(html::HtmlPage::new(), |page, (href, rel)| {
+                page.with_head_link(self.site.href(from, &href), rel)
+            });
+
+        let page = self
+            .site
+            .mention_endpoints()
+            .into_iter()
+            .fold(page, |page, (url, rel)| page.with_head_link(url, rel))
+            .with_stylesheet(self.site.href(from, theme::STYLESHEET_HREF))
+            .with_link_attr("#content", "Skip to content", vec![("class", "skip-link")]);
+
+        match self.site.theme_toggle() {
+            true => page
+                .with_script_link(self.site.href(from, theme::TOGGLE_SCRIPT_HREF))
+                .with_raw(theme::TOGGLE_BUTTON),
+            false => page,
+        }
     }
 }
 
@@ -244,6 +1281,16 @@ impl Library {
 #[derive(Debug)]
 pub struct LibraryHtml {
     pages: Vec<(String, html::HtmlPage)>,
+    lang: Option<String>,
+    languages: Vec<String>,
+    outputs: Vec<(String, String)>,
+    files: Vec<(String, PathBuf)>,
+
+    /// Fingerprinted hrefs of assets, keyed by the href they replace.
+    fingerprints: HashMap<String, String>,
+
+    /// Base URL absolute links to fingerprinted assets begin with, if any.
+    base_url: Option<String>,
 }
 
 impl LibraryHtml {
@@ -258,7 +1305,131 @@ impl LibraryHtml {
     #[inline]
     #[must_use]
     pub fn new(pages: Vec<(String, html::HtmlPage)>) -> Self {
-        Self { pages }
+        Self {
+            pages,
+            lang: None,
+            languages: Vec::new(),
+            outputs: Vec::new(),
+            files: Vec::new(),
+            fingerprints: HashMap::new(),
+            base_url: None,
+        }
+    }
+
+    /// Sets the language every page is declared to be written in.
+    #[inline]
+    #[must_use]
+    pub fn with_lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    /// Sets the languages of a multilingual site, pages within a directory
+    /// named after one of these languages are declared to be written in it
+    /// rather than the language given to [`with_lang`].
+    ///
+    /// [`with_lang`]: LibraryHtml::with_lang
+    #[inline]
+    #[must_use]
+    pub fn with_languages(mut self, languages: &[Rc<str>]) -> Self {
+        self.languages = languages.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    /// Adds a non-HTML output, such as a JSON index, to be written to the
+    /// given href alongside the generated pages.
+    #[inline]
+    #[must_use]
+    pub fn with_output(mut self, href: impl Into<String>, contents: impl Into<String>) -> Self {
+        self.outputs.push((href.into(), contents.into()));
+        self
+    }
+
+    /// Adds files to be copied into the output directory when written, given
+    /// as tuples of the output href and the path of the file to copy.
+    #[inline]
+    #[must_use]
+    pub fn with_files(mut self, files: Vec<(String, PathBuf)>) -> Self {
+        self.files.extend(files);
+        self
+    }
+
+    /// Adds files to be copied into the output directory in place of any
+    /// output or file with the same href, given as tuples of the output href
+    /// and the path of the file to copy.
+    #[must_use]
+    pub fn with_overrides(mut self, files: Vec<(String, PathBuf)>) -> Self {
+        for (href, path) in files {
+            self.outputs.retain(|(h, _)| *h != href);
+            self.files.retain(|(h, _)| *h != href);
+            log::debug!("overriding '{}' with '{}'", href, path.display());
+            self.files.push((href, path));
+        }
+
+        self
+    }
+
+    /// Fingerprints every stylesheet and script to be written, including
+    /// overrides, so that pages link to them by fingerprinted hrefs, see the
+    /// [`fingerprint`] module. Absolute links to them begin with the given
+    /// base URL, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file to be copied could not be read.
+    ///
+    /// [`fingerprint`]: crate::fingerprint
+    pub fn with_fingerprints(mut self, base_url: Option<&str>) -> Result<Self> {
+        for (href, contents) in &mut self.outputs {
+            if fingerprint::applies(href) {
+                let fingerprinted = fingerprint::fingerprinted(href, contents.as_bytes());
+                let original = mem::replace(href, fingerprinted.clone());
+                self.fingerprints.insert(original, fingerprinted);
+            }
+        }
+
+        let mut buffer = ReadBuffer::new();
+
+        for (href, path) in &mut self.files {
+            if fingerprint::applies(href) {
+                let contents = buffer.read(&*path).map_err(|_| Error::FileReadError)?;
+                let fingerprinted = fingerprint::fingerprinted(href, contents);
+                let original = mem::replace(href, fingerprinted.clone());
+                self.fingerprints.insert(original, fingerprinted);
+            }
+        }
+
+        self.base_url = base_url.map(str::to_owned);
+        Ok(self)
+    }
+
+    /// Gets the fingerprinted hrefs of assets, keyed by the href they replace,
+    /// see [`with_fingerprints`].
+    ///
+    /// [`with_fingerprints`]: LibraryHtml::with_fingerprints
+    #[inline]
+    #[must_use]
+    pub fn fingerprints(&self) -> &HashMap<String, String> {
+        &self.fingerprints
+    }
+
+    /// Gets the href of every page, output, and file to be written.
+    pub fn hrefs(&self) -> impl Iterator<Item = &str> {
+        self.pages
+            .iter()
+            .map(|(href, _)| href.as_str())
+            .chain(self.outputs.iter().map(|(href, _)| href.as_str()))
+            .chain(self.files.iter().map(|(href, _)| href.as_str()))
+    }
+
+    /// Finds hrefs to be written which differ only by case, see
+    /// [`case_collisions`].
+    ///
+    /// [`case_collisions`]: href::case_collisions
+    #[inline]
+    #[must_use]
+    pub fn case_collisions(&self) -> Vec<Vec<&str>> {
+        href::case_collisions(self.hrefs())
     }
 
     /// Consumes the given [`LibraryHtml`] and writes it to files, corrosponding
@@ -270,44 +1441,128 @@ impl LibraryHtml {
 
         for (href, page) in self.pages {
             let mut file_path = path.clone();
-            file_path.push(href);
+            file_path.push(&href);
 
             if let Some(p) = file_path.parent() {
                 fs::create_dir_all(p).map_err(|_| Error::DirectoryCreateError)?;
             }
 
-            fs::write(file_path, page.to_html_string()).map_err(|_| Error::FileWriteError)?;
+            let lang = href
+                .split_once('/')
+                .and_then(|(dir, _)| self.languages.iter().find(|l| *l == dir))
+                .or(self.lang.as_ref());
+
+            let page = match lang {
+                // `build_html` has no way of setting attributes on the root
+                // element, so the language is added to the rendered page.
+                Some(lang) => page.to_html_string().replacen(
+                    "<html>",
+                    &format!("<html lang=\"{}\">", lang),
+                    1,
+                ),
+                None => page.to_html_string(),
+            };
+
+            let page = match self.fingerprints.is_empty() {
+                true => page,
+                false => {
+                    fingerprint::rewrite(&page, &href, self.base_url.as_deref(), &self.fingerprints)
+                }
+            };
+
+            fs::write(file_path, page).map_err(|_| Error::FileWriteError)?;
+            log::debug!("wrote '{}'", href);
+        }
+
+        for (href, contents) in self.outputs {
+            let mut file_path = path.clone();
+            file_path.push(&href);
+
+            if let Some(p) = file_path.parent() {
+                fs::create_dir_all(p).map_err(|_| Error::DirectoryCreateError)?;
+            }
+
+            fs::write(file_path, contents).map_err(|_| Error::FileWriteError)?;
+            log::debug!("wrote '{}'", href);
+        }
+
+        for (href, source) in self.files {
+            let mut file_path = path.clone();
+            file_path.push(&href);
+
+            if let Some(p) = file_path.parent() {
+                fs::create_dir_all(p).map_err(|_| Error::DirectoryCreateError)?;
+            }
+
+            fs::copy(&source, file_path).map_err(|_| Error::FileCopyError)?;
+            log::debug!("copied '{}' to '{}'", source.display(), href);
         }
 
         Ok(())
     }
 }
 
+/// A document which differs between libraries merged by [`merge`], and which
+/// of its versions was kept.
+///
+/// [`merge`]: Library::merge
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub path: Rc<str>,
+    pub kept: Kept,
+}
+
+/// Which of two libraries merged by [`merge`] a document was kept from.
+///
+/// [`merge`]: Library::merge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kept {
+    /// The library merged into, as its version is the newest.
+    Ours,
+
+    /// The library merged from, as its version is the newest.
+    Theirs,
+}
+
 /// Holds infomation about a markdown document.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Document {
     name: Rc<str>,
-    hash: u64,
+    hash: Digest,
+
+    /// The algorithm `hash` was made with.
+    #[serde(default)]
+    hash_algorithm: HashAlgorithm,
+
     mod_time: time::OffsetDateTime,
     create_time: time::OffsetDateTime,
 }
 
 impl Document {
-    /// Opens the given path and reads it for info, this will set the
-    /// modification and creation time to the current time and as such
-    /// should be avoided in favor of using methods of [`Library`].
+    /// Opens the given path and reads it for info, into the given
+    /// [`ReadBuffer`], hashing it with the given algorithm. This will set the
+    /// modification and creation time to the current time and as such should
+    /// be avoided in favor of using methods of [`Library`].
     ///
+    /// [`ReadBuffer`]: ReadBuffer
     /// [`Library`]: Library
-    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        let content = MdContent::new(fs::read_to_string(path).map_err(|_| Error::FileReadError)?);
-        let now = time::OffsetDateTime::now_local().unwrap_or(time::OffsetDateTime::now_utc());
+    pub fn open(
+        path: impl AsRef<Path>,
+        algorithm: HashAlgorithm,
+        buffer: &mut ReadBuffer,
+    ) -> Result<Self> {
+        let bytes = buffer.read(path).map_err(|_| Error::FileReadError)?;
+        let hash = algorithm.hash(bytes);
+        let content = MdContent::new(String::from_utf8_lossy(bytes));
+        let now = now();
 
         Ok(Self {
             name: match content.title() {
                 Some(cow_str) => cow_str.as_ref().into(),
                 None => "".into(),
             },
-            hash: content.fnv1_hash(),
+            hash,
+            hash_algorithm: algorithm,
             mod_time: now,
             create_time: now,
         })
@@ -315,37 +1570,68 @@ impl Document {
 
     /// Updates the given [`Document`] by comparing its stored hash of the given
     /// file's content, if they are unequal then the modification time is
-    /// updated to be the current time and the stored hash is updated.
+    /// updated to be the current time. The stored hash is replaced by one made
+    /// with the given algorithm. The file is read into the given
+    /// [`ReadBuffer`].
     ///
     /// [`Document`]: Document
-    /// [`MdContent`]: MdContent
-    pub fn update(self, path: impl AsRef<Path>) -> Result<Self> {
-        let content = MdContent::new(fs::read_to_string(path).map_err(|_| Error::FileReadError)?);
-        let new_hash = content.fnv1_hash();
+    /// [`ReadBuffer`]: ReadBuffer
+    pub fn update(
+        self,
+        path: impl AsRef<Path>,
+        algorithm: HashAlgorithm,
+        buffer: &mut ReadBuffer,
+    ) -> Result<Self> {
+        let bytes = buffer.read(path).map_err(|_| Error::FileReadError)?;
+        let unchanged = self.hash_algorithm.hash(bytes) == self.hash;
+        let hash = algorithm.hash(bytes);
+        let content = MdContent::new(String::from_utf8_lossy(bytes));
 
-        Ok(match self.hash == new_hash {
-            true => self,
+        Ok(match unchanged {
+            true => Self {
+                hash,
+                hash_algorithm: algorithm,
+                ..self
+            },
             false => Self {
                 name: match content.title() {
                     Some(cow_str) => cow_str.as_ref().into(),
                     None => "".into(),
                 },
-                hash: new_hash,
-                mod_time: time::OffsetDateTime::now_local()
-                    .unwrap_or(time::OffsetDateTime::now_utc()),
+                hash,
+                hash_algorithm: algorithm,
+                mod_time: now(),
                 ..self
             },
         })
     }
 
-    /// Returns true if the [`Document`] has changed since its last update. This
-    /// is checked by taking the hash of the given file and comparing it to that
-    /// which is stored within the [`Document`].
+    /// Rehashes the [`Document`] with the given algorithm if it has not
+    /// changed since its last update, returning true if it was rehashed. The
+    /// file is read into the given [`ReadBuffer`].
     ///
     /// [`Document`]: Document
-    pub fn has_changed(&self, path: impl AsRef<Path>) -> Result<bool> {
-        let content = MdContent::new(fs::read_to_string(path).map_err(|_| Error::FileReadError)?);
-        Ok(self.hash != content.fnv1_hash())
+    /// [`ReadBuffer`]: ReadBuffer
+    pub fn migrate(
+        &mut self,
+        path: impl AsRef<Path>,
+        algorithm: HashAlgorithm,
+        buffer: &mut ReadBuffer,
+    ) -> Result<bool> {
+        if self.hash_algorithm == algorithm {
+            return Ok(false);
+        }
+
+        let bytes = buffer.read(path).map_err(|_| Error::FileReadError)?;
+
+        Ok(match self.hash_algorithm.hash(bytes) == self.hash {
+            true => {
+                self.hash = algorithm.hash(bytes);
+                self.hash_algorithm = algorithm;
+                true
+            }
+            false => false,
+        })
     }
 
     /// Gets the time of the last modification as made by either the struct's
@@ -374,6 +1660,41 @@ impl Document {
     }
 }
 
+/// Gets the current local time, or UTC if the local offset cannot be found,
+/// as on some Unix systems while several threads are running.
+fn now() -> time::OffsetDateTime {
+    time::OffsetDateTime::now_local().unwrap_or_else(|_| {
+        log::debug!("could not find the local time offset, using UTC");
+        time::OffsetDateTime::now_utc()
+    })
+}
+
+/// Reads the markdown file at the given path into the given [`ReadBuffer`],
+/// returning its content with any invalid UTF-8 replaced.
+///
+/// [`ReadBuffer`]: ReadBuffer
+fn read_markdown<'a>(path: impl AsRef<Path>, buffer: &'a mut ReadBuffer) -> Result<Cow<'a, str>> {
+    buffer.read_str(path).map_err(|_| Error::FileReadError)
+}
+
+/// Links from a document's page to other pages, see [`document_page`].
+///
+/// [`document_page`]: Library::document_page
+struct DocumentLinks<'a> {
+    /// Language and href of each of the document's translations.
+    alternates: &'a [(&'a str, String)],
+
+    /// Navigation of the series the document is part of, if any.
+    series: Option<Container>,
+
+    /// Href and name of each related document.
+    related: Vec<(String, &'a str)>,
+
+    /// Name of the document's author, and the href of their page if they have
+    /// one.
+    author: Option<(&'a str, Option<String>)>,
+}
+
 /// Represents a result of some library related function.
 pub type Result<T> = result::Result<T, Error>;
 
@@ -408,6 +1729,24 @@ pub enum Error {
     /// I/O failure to write to file.
     FileWriteError,
 
+    /// I/O failure to copy a file.
+    FileCopyError,
+
+    /// A script failed to transform a page.
+    ScriptError,
+
+    /// A document's front matter could not be parsed.
+    FrontMatterError,
+
+    /// A protected document's page could not be encrypted.
+    EncryptionError,
+
+    /// An image referenced by a document could not be optimized.
+    ImageError,
+
+    /// Several documents, series, or authors would be written to one href.
+    HrefCollisionError,
+
     /// Failure to serialize the struct.
     SerializationError,
 }
@@ -433,3 +1772,109 @@ impl From<ffi::OsString> for Error {
         Self::InvalidString
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_tree_test() {
+        let dir = std::env::temp_dir().join("whim-windows-tree-test");
+        fs::create_dir_all(dir.join("blog")).unwrap();
+
+        let post = dir.join("blog").join("post.md");
+        fs::write(&post, "# Post\r\n\r\nWritten on Windows.\r\n").unwrap();
+
+        let doc = Document::open(&post, HashAlgorithm::default(), &mut ReadBuffer::new()).unwrap();
+        assert_eq!(doc.name(), "Post");
+
+        let library = dir.join(".whim.ron");
+        fs::write(
+            &library,
+            ron::to_string(&Library {
+                documents: HashMap::from([("blog\\post.md".into(), doc.clone())]),
+                site: Site::default(),
+                hash_algorithm: HashAlgorithm::default(),
+                commit: None,
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+        let lib = Library::open(&library).unwrap();
+        assert_eq!(lib.documents()["blog/post.md"], doc);
+        assert_eq!(lib.doc_href("blog\\post.md"), "blog/post.html");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn merge_test() {
+        let doc = |hash: u64, created: i64, modified: i64| Document {
+            name: "Post".into(),
+            hash: HashAlgorithm::Xxh3.hash(hash.to_string()),
+            hash_algorithm: HashAlgorithm::Xxh3,
+            mod_time: time::OffsetDateTime::from_unix_timestamp(modified).unwrap(),
+            create_time: time::OffsetDateTime::from_unix_timestamp(created).unwrap(),
+        };
+        let lib = |documents: Vec<(&str, Document)>| Library {
+            documents: documents.into_iter().map(|(p, d)| (p.into(), d)).collect(),
+            site: Site::default(),
+            hash_algorithm: HashAlgorithm::Xxh3,
+            commit: Some("c0ffee".into()),
+        };
+
+        let mut ours = lib(vec![
+            ("a.md", doc(1, 10, 10)),
+            ("b.md", doc(1, 10, 30)),
+            ("c.md", doc(1, 10, 20)),
+        ]);
+        let theirs = lib(vec![
+            ("b.md", doc(2, 5, 20)),
+            ("c.md", doc(1, 10, 25)),
+            ("d.md", doc(1, 10, 10)),
+        ]);
+
+        let conflicts = ours.merge(theirs);
+
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                path: "b.md".into(),
+                kept: Kept::Ours,
+            }]
+        );
+        assert_eq!(ours.documents().len(), 4);
+        assert_eq!(ours.documents()["b.md"].hash, doc(1, 0, 0).hash);
+        assert_eq!(
+            ours.documents()["b.md"].create_time,
+            doc(1, 5, 0).create_time
+        );
+        assert_eq!(ours.documents()["c.md"].mod_time, doc(1, 0, 25).mod_time);
+        assert_eq!(ours.commit(), None);
+    }
+
+    #[test]
+    fn write_nested_test() {
+        let dir = std::env::temp_dir().join("whim-write-nested-test");
+        fs::create_dir_all(dir.join("blog").join("img")).unwrap();
+
+        let image = dir.join("blog").join("img").join("a.gif");
+        fs::write(&image, "GIF89a").unwrap();
+
+        let out = dir.join("out");
+        LibraryHtml::new(Vec::new())
+            .with_output("series/notes/index.json", "[]")
+            .with_files(vec![("blog/img/a.gif".to_owned(), image)])
+            .write(&out)
+            .unwrap();
+
+        assert_eq!(fs::read(out.join("blog/img/a.gif")).unwrap(), b"GIF89a");
+        assert_eq!(
+            fs::read_to_string(out.join("series/notes/index.json")).unwrap(),
+            "[]"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
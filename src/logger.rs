@@ -0,0 +1,136 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! A [`log`] logger which writes records to standard error and, optionally, to
+//! a log file. Only warnings and errors are written by default, each
+//! `--verbose` flag lowers the level by one, down to tracing.
+
+use crate::style::Style;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+};
+use time::format_description::well_known::Rfc3339;
+
+/// Writes log records at or above its level, see the [module] documentation.
+///
+/// [module]: crate::logger
+#[derive(Debug)]
+pub struct Logger {
+    level: LevelFilter,
+    file: Option<Mutex<File>>,
+}
+
+impl Logger {
+    /// Creates a new [`Logger`] for the given number of `--verbose` flags.
+    ///
+    /// [`Logger`]: Logger
+    #[must_use]
+    pub fn new(verbosity: usize) -> Self {
+        Self {
+            level: level(verbosity),
+            file: None,
+        }
+    }
+
+    /// Appends records to the file at the given path as well, creating it if
+    /// it does not exist. Records in the file are timestamped.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error encountered while opening the file.
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.file = Some(Mutex::new(file));
+        Ok(self)
+    }
+
+    /// Sets this [`Logger`] as the logger used by the [`log`] macros.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a logger has already been set.
+    ///
+    /// [`Logger`]: Logger
+    pub fn init(self) -> Result<(), log::SetLoggerError> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Leave out records from dependencies.
+        metadata.level() <= self.level && metadata.target().starts_with(env!("CARGO_PKG_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let label = match record.level() {
+            Level::Error => Style::Failure.paint("error"),
+            Level::Warn => Style::Warning.paint("warning"),
+            level => level.as_str().to_lowercase(),
+        };
+
+        eprintln!("{}: {}", label, record.args());
+
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let now = time::OffsetDateTime::now_utc();
+
+            // A logger has nowhere to report its own errors.
+            let _ = writeln!(
+                file,
+                "{} {:<5} {}: {}",
+                now.format(&Rfc3339).unwrap_or_default(),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Gets the level to log at for the given number of `--verbose` flags.
+#[must_use]
+pub fn level(verbosity: usize) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_test() {
+        assert_eq!(level(0), LevelFilter::Warn);
+        assert_eq!(level(2), LevelFilter::Debug);
+        assert_eq!(level(7), LevelFilter::Trace);
+
+        let logger = Logger::new(1);
+        let metadata = |level, target| Metadata::builder().level(level).target(target).build();
+
+        assert!(logger.enabled(&metadata(Level::Info, "whim::library")));
+        assert!(!logger.enabled(&metadata(Level::Debug, "whim::library")));
+        assert!(!logger.enabled(&metadata(Level::Warn, "glob")));
+    }
+}
@@ -2,14 +2,47 @@
 // Licensed under the MIT License.
 // See LICENSE file in repository root for full text.
 
+mod archive;
 mod args;
+mod authors;
+mod buffer;
 mod commands;
+mod comments;
+mod config;
+mod data;
+mod diagnostic;
+mod epub;
+mod escape;
+mod fingerprint;
 mod fnv1_hash;
+mod front_matter;
+mod git;
+mod graph;
+mod hash;
+mod hooks;
+mod hosting;
 mod href;
+mod i18n;
+mod images;
+mod lang;
+mod layout;
 mod library;
+mod logger;
 mod md_content;
+mod pdf;
 mod prompt;
-use args::{ArgsParser, Command};
+mod protect;
+mod related;
+mod scripts;
+mod search;
+mod series;
+mod share;
+mod site;
+mod style;
+mod theme;
+mod webmention;
+use args::{ArgsParser, Command, Flag};
+use i18n::Message;
 use std::{env, error::Error};
 
 const NEW_COMMAND: &str = "new";
@@ -17,6 +50,17 @@ const UPDATE_COMMAND: &str = "update";
 const SCAN_COMMAND: &str = "scan";
 const ADD_COMMAND: &str = "add";
 const BUILD_COMMAND: &str = "build";
+const WEBMENTION_COMMAND: &str = "webmention";
+const EXPORT_PDF_COMMAND: &str = "export-pdf";
+const EPUB_COMMAND: &str = "epub";
+const MERGE_COMMAND: &str = "merge";
+
+const YES_FLAG: &str = "yes";
+const YES_FLAG_SHORT: &str = "y";
+const VERBOSE_FLAG: &str = "verbose";
+const VERBOSE_FLAG_SHORT: &str = "v";
+const LOG_FILE_FLAG: &str = "log-file";
+const CONFIG_FLAG: &str = "config";
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cmd_new = Command(NEW_COMMAND.into());
@@ -24,6 +68,16 @@ fn main() -> Result<(), Box<dyn Error>> {
     let cmd_scan = Command(SCAN_COMMAND.into());
     let cmd_add = Command(ADD_COMMAND.into());
     let cmd_build = Command(BUILD_COMMAND.into());
+    let cmd_webmention = Command(WEBMENTION_COMMAND.into());
+    let cmd_export_pdf = Command(EXPORT_PDF_COMMAND.into());
+    let cmd_epub = Command(EPUB_COMMAND.into());
+    let cmd_merge = Command(MERGE_COMMAND.into());
+    let flag_yes = Flag::Bool(YES_FLAG.into());
+    let flag_yes_short = Flag::Bool(YES_FLAG_SHORT.into());
+    let flag_verbose = Flag::Bool(VERBOSE_FLAG.into());
+    let flag_verbose_short = Flag::Bool(VERBOSE_FLAG_SHORT.into());
+    let flag_log_file = Flag::String(LOG_FILE_FLAG.into());
+    let flag_config = Flag::String(CONFIG_FLAG.into());
 
     let args = match ArgsParser::new(env::args())
         .command(cmd_new)
@@ -31,6 +85,16 @@ fn main() -> Result<(), Box<dyn Error>> {
         .command(cmd_scan)
         .command(cmd_add.clone())
         .command(cmd_build.clone())
+        .command(cmd_webmention.clone())
+        .command(cmd_export_pdf.clone())
+        .command(cmd_epub.clone())
+        .command(cmd_merge.clone())
+        .flag(flag_yes.clone())
+        .flag(flag_yes_short.clone())
+        .flag(flag_verbose.clone())
+        .flag(flag_verbose_short.clone())
+        .flag(flag_log_file.clone())
+        .flag(flag_config.clone())
         .parse()
     {
         Ok(v) => v,
@@ -40,11 +104,47 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     };
 
+    let flags = args.flags();
+    let logger = logger::Logger::new(args.count(&flag_verbose) + args.count(&flag_verbose_short));
+
+    let logger = match flags.get(&flag_log_file) {
+        Some(Some(args::Value::String(path))) => match logger.with_file(path) {
+            Ok(logger) => logger,
+            Err(_) => {
+                println!("{}", Message::CouldNotOpenLogFile.format(&[path]));
+                return Ok(());
+            }
+        },
+        _ => logger,
+    };
+
+    logger.init()?;
+
+    if [flag_yes, flag_yes_short]
+        .iter()
+        .any(|flag| matches!(flags.get(flag), Some(Some(args::Value::Bool(true)))))
+    {
+        prompt::set_assume_yes(true);
+    }
+
+    let config_path = match flags.get(&flag_config) {
+        Some(Some(args::Value::String(path))) => path.as_str(),
+        _ => config::CONFIG_FILE,
+    };
+
+    let config = match config::Config::open(config_path) {
+        Ok(config) => config,
+        Err(_) => {
+            println!("{}", Message::CouldNotReadConfig.format(&[&config_path]));
+            return Ok(());
+        }
+    };
+
     let command = {
         let cmds = args.commands();
 
         if cmds.len() > 1 {
-            println!("Only singlular commands permitted.");
+            println!("{}", Message::SingleCommand.text());
             return Ok(());
         } else if cmds.len() < 1 {
             print_help();
@@ -55,34 +155,69 @@ fn main() -> Result<(), Box<dyn Error>> {
     };
 
     match &*command.0 {
-        NEW_COMMAND => return commands::new(),
-        UPDATE_COMMAND => return commands::update(),
-        SCAN_COMMAND => return commands::scan(),
+        NEW_COMMAND => return commands::new(&config),
+        UPDATE_COMMAND => return commands::update(&config),
+        SCAN_COMMAND => return commands::scan(&config),
         ADD_COMMAND => {
             let params = args.command_parameters(cmd_add).unwrap();
 
-            if params.len() < 1 {
-                println!("add requires a parameter, e.g. 'whim add doc.md'");
-                return Ok(());
-            }
+            return commands::add(
+                &config,
+                params.first().map(|param| match param {
+                    args::Value::String(s) => s.clone(),
+                    _ => unreachable!(),
+                }),
+            );
+        }
+        BUILD_COMMAND => {
+            let params = args.command_parameters(cmd_build).unwrap();
 
-            return commands::add(match &params[0] {
+            return commands::build(
+                &config,
+                params.first().map(|param| match param {
+                    args::Value::String(s) => s.clone(),
+                    _ => unreachable!(),
+                }),
+            );
+        }
+        WEBMENTION_COMMAND => {
+            let params = args.command_parameters(cmd_webmention).unwrap();
+
+            return commands::webmention(params.first().map(|param| match param {
+                args::Value::String(s) => s.clone(),
+                _ => unreachable!(),
+            }));
+        }
+        EXPORT_PDF_COMMAND => {
+            let params = args.command_parameters(cmd_export_pdf).unwrap();
+            let mut params = params.iter().map(|param| match param {
                 args::Value::String(s) => s.clone(),
                 _ => unreachable!(),
             });
+
+            return commands::export_pdf(&config, params.next(), params.next());
         }
-        BUILD_COMMAND => {
-            let params = args.command_parameters(cmd_build).unwrap();
+        EPUB_COMMAND => {
+            let params = args.command_parameters(cmd_epub).unwrap();
 
-            if params.len() < 1 {
-                println!("build requires a parameter, e.g. 'whim add /path/to/dir/'");
-                return Ok(());
-            }
+            return commands::epub(
+                &config,
+                params
+                    .into_iter()
+                    .map(|param| match param {
+                        args::Value::String(s) => s,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            );
+        }
+        MERGE_COMMAND => {
+            let params = args.command_parameters(cmd_merge).unwrap();
 
-            return commands::build(match &params[0] {
+            return commands::merge(params.first().map(|param| match param {
                 args::Value::String(s) => s.clone(),
                 _ => unreachable!(),
-            });
+            }));
         }
         _ => (),
     };
@@ -91,17 +226,5 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 fn print_help() {
-    println!(
-        "\
-        whim\n\
-        \n\
-        Usage: whim [COMMAND]\n\
-        \n\
-        Commands:\n\
-        \tnew      Creates new library in the current directory.\n\
-        \tupdate   Updates the library in the current directory.\n\
-        \tscan     Scans the directory for new files.\n\
-        \tadd      Add a document.\
-        "
-    )
+    println!("{}", Message::Help.text())
 }
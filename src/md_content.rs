@@ -2,10 +2,10 @@
 // Licensed under the MIT License.
 // See LICENSE file in repository root for full text.
 
-use crate::fnv1_hash::Hashable;
+use crate::{front_matter, href};
 use build_html as html;
 use pulldown_cmark as md;
-use std::{borrow::Cow, rc::Rc};
+use std::{borrow::Cow, collections::HashMap, iter, rc::Rc};
 
 #[derive(Debug, Clone)]
 pub struct MdContent {
@@ -14,14 +14,16 @@ pub struct MdContent {
 
 /// Represents a peice of markdown content.
 impl MdContent {
-    /// Creates a new [`MdContent`] given a markdown string.
+    /// Creates a new [`MdContent`] given a markdown string, leaving out any
+    /// [front matter].
     ///
+    /// [front matter]: crate::front_matter
     /// [`MdContent`]: MdContent
     #[inline]
     #[must_use]
     pub fn new(md_string: impl AsRef<str>) -> Self {
         Self {
-            md_string: md_string.as_ref().into(),
+            md_string: front_matter::strip(md_string.as_ref()).into(),
         }
     }
 
@@ -61,19 +63,301 @@ impl MdContent {
 
         None
     }
+
+    /// Gets an anchor for each heading of the [`MdContent`], in order, made by
+    /// slugifying the heading's text. Repeated anchors are made unique with a
+    /// numbered suffix, e.g. "notes", "notes-1".
+    ///
+    /// [`MdContent`]: MdContent
+    #[must_use]
+    pub fn heading_anchors(&self) -> Vec<String> {
+        let mut anchors = Vec::new();
+        let mut heading: Option<String> = None;
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for event in md::Parser::new_ext(&self.md_string, md::Options::all()) {
+            match event {
+                md::Event::Start(md::Tag::Heading(..)) => heading = Some(String::new()),
+                md::Event::End(md::Tag::Heading(..)) => {
+                    let slug = href::slugify(&heading.take().unwrap_or_default());
+                    let count = seen.entry(slug.clone()).or_default();
+
+                    anchors.push(match *count {
+                        0 => slug,
+                        n => format!("{}-{}", slug, n),
+                    });
+
+                    *count += 1;
+                }
+                md::Event::Text(cs) | md::Event::Code(cs) if heading.is_some() => {
+                    heading.get_or_insert_with(String::new).push_str(&cs)
+                }
+                _ => continue,
+            }
+        }
+
+        anchors
+    }
+
+    /// Gets the destination of every link in the [`MdContent`], in order.
+    ///
+    /// [`MdContent`]: MdContent
+    #[must_use]
+    pub fn links(&self) -> Vec<String> {
+        md::Parser::new_ext(&self.md_string, md::Options::all())
+            .filter_map(|event| match event {
+                md::Event::Start(md::Tag::Link(_, dest, _)) => Some(dest.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Gets the source of every image in the [`MdContent`], in order.
+    ///
+    /// [`MdContent`]: MdContent
+    #[must_use]
+    pub fn images(&self) -> Vec<String> {
+        md::Parser::new_ext(&self.md_string, md::Options::all())
+            .filter_map(|event| match event {
+                md::Event::Start(md::Tag::Image(_, dest, _)) => Some(dest.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Gets the plain text of the [`MdContent`], with all markdown syntax
+    /// removed and block level elements separated by newlines.
+    ///
+    /// [`MdContent`]: MdContent
+    #[must_use]
+    pub fn text(&self) -> String {
+        md::Parser::new_ext(&self.md_string, md::Options::all()).fold(
+            String::new(),
+            |mut acc, event| {
+                match event {
+                    md::Event::Text(cs) | md::Event::Code(cs) => acc.push_str(&cs),
+                    md::Event::SoftBreak => acc.push(' '),
+                    md::Event::HardBreak | md::Event::End(_) if !acc.ends_with('\n') => {
+                        acc.push('\n')
+                    }
+                    _ => (),
+                }
+
+                acc
+            },
+        )
+    }
+
+    /// Gets an excerpt of at most `len` characters from the first paragraph
+    /// of the [`MdContent`]. Words are not split, an ellipsis is appended if
+    /// the paragraph had to be shortened.
+    ///
+    /// [`MdContent`]: MdContent
+    #[must_use]
+    pub fn excerpt(&self, len: usize) -> String {
+        let mut parser = md::Parser::new(&self.md_string);
+        let mut paragraph = String::new();
+
+        // Skip everything up to the first paragraph.
+        for event in parser.by_ref() {
+            if let md::Event::Start(md::Tag::Paragraph) = event {
+                break;
+            }
+        }
+
+        for event in parser {
+            match event {
+                md::Event::Text(cs) | md::Event::Code(cs) => paragraph.push_str(&cs),
+                md::Event::SoftBreak | md::Event::HardBreak => paragraph.push(' '),
+                md::Event::End(md::Tag::Paragraph) => break,
+                _ => continue,
+            }
+        }
+
+        if paragraph.chars().count() <= len {
+            return paragraph;
+        }
+
+        let mut excerpt = String::new();
+
+        for word in paragraph.split_whitespace() {
+            if excerpt.chars().count() + word.chars().count() + 1 > len {
+                break;
+            }
+
+            if !excerpt.is_empty() {
+                excerpt.push(' ');
+            }
+
+            excerpt.push_str(word);
+        }
+
+        excerpt + "…"
+    }
+
+    /// Renders the [`MdContent`] as HTML like [`to_html_string`], giving the
+    /// text of each footnote as a sidenote beside its reference as well. The
+    /// theme shows sidenotes in the margin of wide pages, and footnotes at the
+    /// end of narrow ones. Footnotes of more than a paragraph are only given
+    /// at the end.
+    ///
+    /// [`MdContent`]: MdContent
+    /// [`to_html_string`]: html::Html::to_html_string
+    #[must_use]
+    pub fn to_html_with_sidenotes(&self) -> String {
+        self.html(true)
+    }
+
+    /// Renders the [`MdContent`] as HTML, with sidenotes if asked for.
+    ///
+    /// [`MdContent`]: MdContent
+    fn html(&self, sidenotes: bool) -> String {
+        let anchors = self.heading_anchors();
+        let mut anchors_iter = anchors.iter();
+
+        // Give each heading without an explicit id its anchor.
+        let events = md::Parser::new_ext(&self.md_string, md::Options::all())
+            .map(|event| match event {
+                md::Event::Start(md::Tag::Heading(level, id, classes)) => {
+                    let anchor = anchors_iter.next().map(String::as_str);
+                    md::Event::Start(md::Tag::Heading(level, id.or(anchor), classes))
+                }
+                e => e,
+            })
+            .collect::<Vec<_>>();
+
+        let notes = match sidenotes {
+            true => footnotes(&events),
+            false => HashMap::new(),
+        };
+
+        // Footnotes are numbered as they are first referenced or defined, as
+        // they are when rendered. Those given as sidenotes are wrapped so that
+        // the theme may hide them where sidenotes are shown.
+        let mut numbers = HashMap::new();
+        let events = events.into_iter().flat_map(|event| {
+            let mut before = None;
+            let after = match &event {
+                md::Event::FootnoteReference(label) => {
+                    let next = numbers.len() + 1;
+                    let number = *numbers.entry(label.clone()).or_insert(next);
+
+                    notes.get(label).map(|note| {
+                        md::Event::Html(
+                            format!(
+                                r#"<span class="sidenote"><sup>{}</sup> {}</span>"#,
+                                number, note
+                            )
+                            .into(),
+                        )
+                    })
+                }
+                md::Event::Start(md::Tag::FootnoteDefinition(label)) => {
+                    let next = numbers.len() + 1;
+                    numbers.entry(label.clone()).or_insert(next);
+                    before = notes
+                        .get(label)
+                        .map(|_| md::Event::Html("<div class=\"sidenoted\">\n".into()));
+                    None
+                }
+                md::Event::End(md::Tag::FootnoteDefinition(label)) => {
+                    notes.get(label).map(|_| md::Event::Html("</div>\n".into()))
+                }
+                _ => None,
+            };
+
+            before.into_iter().chain(iter::once(event)).chain(after)
+        });
+
+        let mut html_string = String::new();
+        md::html::push_html(&mut html_string, events);
+        html_string
+    }
+}
+
+/// Renders the text of each footnote defined by the given events, keyed by its
+/// label, to be given inline as a sidenote. A sidenote sits within a paragraph
+/// so may only hold inline content, footnotes of anything more than a single
+/// paragraph, such as lists or code, are left out and only given at the end.
+fn footnotes<'a>(events: &[md::Event<'a>]) -> HashMap<md::CowStr<'a>, String> {
+    let mut notes = HashMap::new();
+    let mut note: Option<(&md::CowStr, usize)> = None;
+
+    for (i, event) in events.iter().enumerate() {
+        match event {
+            md::Event::Start(md::Tag::FootnoteDefinition(label)) => note = Some((label, i + 1)),
+            md::Event::End(md::Tag::FootnoteDefinition(_)) => {
+                if let Some((label, start)) = note.take() {
+                    match paragraph(&events[start..i]) {
+                        Some(inline) => {
+                            let mut html_string = String::new();
+                            md::html::push_html(&mut html_string, inline.iter().cloned());
+                            notes.insert(label.clone(), html_string.trim().to_owned());
+                        }
+                        None => {
+                            log::debug!("footnote '{}' is not inline, no sidenote given", label)
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    notes
+}
+
+/// Gets the inline content of the given events if they are a single paragraph.
+fn paragraph<'a, 'b>(events: &'b [md::Event<'a>]) -> Option<&'b [md::Event<'a>]> {
+    match events {
+        [md::Event::Start(md::Tag::Paragraph), inline @ .., md::Event::End(md::Tag::Paragraph)]
+            if !inline
+                .iter()
+                .any(|e| matches!(e, md::Event::End(md::Tag::Paragraph))) =>
+        {
+            Some(inline)
+        }
+        _ => None,
+    }
 }
 
 impl html::Html for MdContent {
     fn to_html_string(&self) -> String {
-        let parser = md::Parser::new_ext(&self.md_string, md::Options::all());
-        let mut html_string = String::new();
-        md::html::push_html(&mut html_string, parser);
-        html_string
+        self.html(false)
     }
 }
 
-impl Hashable for MdContent {
-    fn fnv1_hash(&self) -> u64 {
-        self.md_string.as_bytes().fnv1_hash()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html::Html;
+
+    #[test]
+    fn sidenotes_test() {
+        let md =
+            MdContent::new("# Notes\n\nFirst[^a] and second[^b].\n\n[^b]: *Two*.\n\n[^a]: One.\n");
+        let html = md.to_html_with_sidenotes();
+
+        assert!(html.contains(concat!(
+            r##"<sup class="footnote-reference"><a href="#a">1</a></sup>"##,
+            r#"<span class="sidenote"><sup>1</sup> One.</span>"#
+        )));
+        assert!(html.contains(r#"<span class="sidenote"><sup>2</sup> <em>Two</em>.</span>"#));
+        assert!(html.contains(concat!(
+            "<div class=\"sidenoted\">\n",
+            r#"<div class="footnote-definition" id="b">"#
+        )));
+        assert!(!md.to_html_string().contains("sidenote"));
+
+        // Block content is left to the end of the page.
+        let md =
+            MdContent::new("Listed[^a] and coded[^b].\n\n[^a]: - One\n\n[^b]: ```\nlet b;\n```\n");
+        let html = md.to_html_with_sidenotes();
+
+        assert!(!html.contains("sidenote"));
+        assert!(html.contains(r#"<div class="footnote-definition" id="a">"#));
+        assert!(html.contains(r#"<div class="footnote-definition" id="b">"#));
+        assert!(html.contains("<li>One</li>"));
+        assert!(html.contains("let b;"));
     }
 }
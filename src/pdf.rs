@@ -0,0 +1,226 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! PDF export of documents. Pages are built as they would be for the site and
+//! printed by a headless Chromium based browser or by `wkhtmltopdf`, whichever
+//! is found first unless a renderer is configured.
+
+use std::{
+    env, error,
+    ffi::OsString,
+    fmt, fs,
+    path::{self, Path, PathBuf},
+    process, result,
+};
+
+/// Programs tried, in order, when no renderer is configured.
+pub const RENDERERS: [&str; 5] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "microsoft-edge",
+    "wkhtmltopdf",
+];
+
+/// A temporary directory the site is written to so that its pages may be
+/// rendered, removed along with its contents when dropped so that it is left
+/// behind by no export, however it ends.
+#[derive(Debug)]
+pub struct SiteDir {
+    path: PathBuf,
+}
+
+impl SiteDir {
+    /// Creates a [`SiteDir`] within the system's temporary directory, named for
+    /// this process. The directory itself is made when the site is written.
+    ///
+    /// [`SiteDir`]: SiteDir
+    #[must_use]
+    pub fn temp() -> Self {
+        Self {
+            path: env::temp_dir().join(format!("whim-pdf-{}", process::id())),
+        }
+    }
+
+    /// Gets the path of the [`SiteDir`].
+    ///
+    /// [`SiteDir`]: SiteDir
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SiteDir {
+    fn drop(&mut self) {
+        if self.path.exists() && fs::remove_dir_all(&self.path).is_err() {
+            log::warn!("could not remove '{}'", self.path.display());
+        }
+    }
+}
+
+/// Finds the program to render PDFs with, being the configured one if given or
+/// else the first of [`RENDERERS`] which can be run.
+///
+/// [`RENDERERS`]: RENDERERS
+#[must_use]
+pub fn find_renderer(configured: Option<&Path>) -> Option<PathBuf> {
+    match configured {
+        Some(path) => Some(path.to_owned()),
+        None => RENDERERS
+            .iter()
+            .find(|renderer| {
+                process::Command::new(renderer)
+                    .arg("--version")
+                    .output()
+                    .is_ok_and(|output| output.status.success())
+            })
+            .map(PathBuf::from),
+    }
+}
+
+/// Renders the HTML page at the given path to a PDF at the given path with the
+/// given renderer, creating the PDF's directory if needed.
+///
+/// # Errors
+///
+/// Returns an error if the directory could not be created, or if the renderer
+/// could not be run or did not succeed.
+pub fn render(renderer: &Path, page: &Path, pdf: &Path) -> Result<()> {
+    let page = page.canonicalize().map_err(|_| Error::PageNotFound)?;
+
+    if let Some(dir) = pdf.parent() {
+        fs::create_dir_all(dir).map_err(|_| Error::FileWriteError)?;
+    }
+
+    // Browsers resolve a relative output path against their own directory.
+    let pdf = path::absolute(pdf).map_err(|_| Error::FileWriteError)?;
+
+    log::debug!(
+        "rendering '{}' to '{}' with '{}'",
+        page.display(),
+        pdf.display(),
+        renderer.display()
+    );
+
+    let output = process::Command::new(renderer)
+        .args(args(renderer, &page, &pdf))
+        .output()
+        .map_err(|_| Error::SpawnError)?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => {
+            log::error!("{}", String::from_utf8_lossy(&output.stderr).trim());
+            Err(Error::RenderFailed)
+        }
+    }
+}
+
+/// Gets the arguments which have the given renderer print the given page to
+/// the given PDF.
+fn args(renderer: &Path, page: &Path, pdf: &Path) -> Vec<OsString> {
+    let wkhtmltopdf = renderer
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().contains("wkhtmltopdf"));
+
+    match wkhtmltopdf {
+        true => vec![
+            "--quiet".into(),
+            "--enable-local-file-access".into(),
+            page.into(),
+            pdf.into(),
+        ],
+        false => {
+            let mut print = OsString::from("--print-to-pdf=");
+            print.push(pdf);
+
+            vec![
+                "--headless".into(),
+                "--disable-gpu".into(),
+                "--no-pdf-header-footer".into(),
+                print,
+                page.into(),
+            ]
+        }
+    }
+}
+
+/// The result type of PDF related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while rendering a PDF.
+#[derive(Debug)]
+pub enum Error {
+    /// The page to render does not exist.
+    PageNotFound,
+
+    /// I/O failure to create the PDF's directory.
+    FileWriteError,
+
+    /// The renderer could not be started, it may not be installed.
+    SpawnError,
+
+    /// The renderer exited unsuccessfully.
+    RenderFailed,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_test() {
+        let page = Path::new("/tmp/site/a.html");
+        let pdf = Path::new("pdf/a.pdf");
+
+        assert_eq!(
+            args(Path::new("/usr/bin/wkhtmltopdf"), page, pdf),
+            [
+                "--quiet",
+                "--enable-local-file-access",
+                "/tmp/site/a.html",
+                "pdf/a.pdf"
+            ]
+        );
+        assert_eq!(
+            args(Path::new("chromium"), page, pdf),
+            [
+                "--headless",
+                "--disable-gpu",
+                "--no-pdf-header-footer",
+                "--print-to-pdf=pdf/a.pdf",
+                "/tmp/site/a.html"
+            ]
+        );
+        assert_eq!(
+            find_renderer(Some(Path::new("my-browser"))),
+            Some(PathBuf::from("my-browser"))
+        );
+    }
+
+    #[test]
+    fn site_dir_test() {
+        let site = SiteDir::temp();
+        let path = site.path().to_owned();
+
+        fs::create_dir_all(path.join("blog")).unwrap();
+        fs::write(path.join("blog").join("a.html"), "<h1>A</h1>").unwrap();
+
+        drop(site);
+        assert!(!path.exists());
+
+        // Dropping before anything was written is fine too.
+        drop(SiteDir::temp());
+    }
+}
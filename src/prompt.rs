@@ -2,13 +2,55 @@
 // Licensed under the MIT License.
 // See LICENSE file in repository root for full text.
 
+use crate::{
+    i18n::{self, Locale, Message},
+    style::Style,
+};
 use std::{
-    error,
+    env, error,
+    ffi::OsStr,
     fmt::{self, Display},
-    io::{self, Write},
+    io::{self, IsTerminal, Write},
+    ops::RangeBounds,
+    path::{Path, PathBuf},
     result,
+    sync::atomic::{AtomicBool, Ordering},
 };
 
+/// Environment variable which, if set to anything other than "" or "0", makes
+/// every prompt answer itself as if [`set_assume_yes`] had been called.
+///
+/// [`set_assume_yes`]: set_assume_yes
+pub const ASSUME_YES_VAR: &str = "WHIM_ASSUME_YES";
+
+/// Set by [`set_assume_yes`] for the whole process.
+///
+/// [`set_assume_yes`]: set_assume_yes
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Number of times [`from_prompt_strict`] will prompt the user before giving
+/// up on getting a recognized answer.
+///
+/// [`from_prompt_strict`]: PromptItem::from_prompt_strict
+pub const STRICT_ATTEMPTS: usize = 3;
+
+/// What a prompt should do when standard input is not a terminal, e.g. when
+/// input is piped in or whim is run in CI, and there is nobody to answer it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum NonInteractive {
+    /// Use the default answer, as if the user gave blank input.
+    #[default]
+    Default,
+
+    /// Answer "yes" if the prompt has such an answer, otherwise fail.
+    AssumeYes,
+
+    /// Fail with [`Error::NotInteractive`].
+    ///
+    /// [`Error::NotInteractive`]: Error::NotInteractive
+    Fail,
+}
+
 /// A yes or no prompt defaulting to yes.
 #[derive(PartialEq, Eq, Clone, Copy, Default)]
 pub enum Yes {
@@ -21,14 +63,27 @@ pub enum Yes {
 }
 
 impl PromptItem for Yes {
-    const OPTIONS: &'static str = "Y/n";
+    fn options() -> &'static str {
+        Message::YesOptions.text()
+    }
 
     fn parse_input(input: String) -> Result<Self> {
-        match input.to_lowercase().as_str() {
-            "n\n" | "no\n" => Ok(Self::No),
+        match answer(&input, i18n::locale()) {
+            Some(false) => Ok(Self::No),
             _ => Ok(Self::Yes),
         }
     }
+
+    fn parse_strict(input: &str) -> Option<Self> {
+        match answer(input, i18n::locale())? {
+            true => Some(Self::Yes),
+            false => Some(Self::No),
+        }
+    }
+
+    fn assumed_yes() -> Option<Self> {
+        Some(Self::Yes)
+    }
 }
 
 /// A yes or no prompt defaulting to yes.
@@ -43,30 +98,106 @@ pub enum No {
 }
 
 impl PromptItem for No {
-    const OPTIONS: &'static str = "y/N";
+    fn options() -> &'static str {
+        Message::NoOptions.text()
+    }
 
     fn parse_input(input: String) -> Result<Self> {
-        match input.to_lowercase().as_str() {
-            "y" | "yes" => Ok(Self::Yes),
+        match answer(&input, i18n::locale()) {
+            Some(true) => Ok(Self::Yes),
             _ => Ok(Self::No),
         }
     }
+
+    fn parse_strict(input: &str) -> Option<Self> {
+        match answer(input, i18n::locale())? {
+            true => Some(Self::Yes),
+            false => Some(Self::No),
+        }
+    }
+
+    fn assumed_yes() -> Option<Self> {
+        Some(Self::Yes)
+    }
+}
+
+/// Parses an answer to a yes or no prompt in the given [`Locale`], giving true
+/// for "yes", false for "no", and [`None`] for anything else.
+///
+/// [`Locale`]: Locale
+/// [`None`]: None
+fn answer(input: &str, locale: Locale) -> Option<bool> {
+    // Trimmed since lines end with "\r\n" on Windows.
+    let input = input.trim().to_lowercase();
+
+    match (
+        locale.yes_answers().contains(&input.as_str()),
+        locale.no_answers().contains(&input.as_str()),
+    ) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+impl PromptItem for String {
+    fn options() -> &'static str {
+        Message::TextOptions.text()
+    }
+
+    fn parse_input(input: String) -> Result<Self> {
+        Ok(input.trim_end_matches(['\r', '\n']).to_owned())
+    }
+}
+
+impl PromptItem for PathBuf {
+    fn options() -> &'static str {
+        Message::PathOptions.text()
+    }
+
+    fn parse_input(input: String) -> Result<Self> {
+        Ok(input.trim().into())
+    }
 }
 
+/// Implements [`PromptItem`] for numeric types, which are parsed from input
+/// with surrounding whitespace removed.
+///
+/// [`PromptItem`]: PromptItem
+macro_rules! numeric_prompt_item {
+    ($options:expr => $($t:ty),+) => {
+        $(
+            impl PromptItem for $t {
+                fn options() -> &'static str {
+                    $options.text()
+                }
+
+                fn parse_input(input: String) -> Result<Self> {
+                    input.trim().parse().map_err(|_| Error::Input)
+                }
+            }
+        )+
+    };
+}
+
+numeric_prompt_item!(Message::IntegerOptions => u16, u32, u64, usize, i32, i64);
+numeric_prompt_item!(Message::NumberOptions => f32, f64);
+
 /// Represents a item that can be constructed based off of prompted user input.
 pub trait PromptItem: Sized {
-    /// Options string to present to the user. A yes/no prompt could use these:
-    /// "Y/n", "y/N", "Yes/no", "YES/no", etc. Capatalize an option if it is a
-    /// default.
+    /// Options string to present to the user, in the process' locale. A yes/no
+    /// prompt could use these: "Y/n", "y/N", "Yes/no", "YES/no", etc.
+    /// Capatalize an option if it is a default.
     ///
     /// It would also be reasonable to explain the type of data, for instance if
     /// constructing an [`i32`]: "integer" or "int" may be appropriate.
     ///
     /// [`i32`]: i32
-    const OPTIONS: &'static str;
+    fn options() -> &'static str;
 
     /// Outputs a prompt to the user and waits for input, then creates a new
-    /// [`Self`].
+    /// [`Self`]. Like every prompt, this is answered immediately without any
+    /// input if [`assume_yes`] is true.
     ///
     /// # Errors
     ///
@@ -75,17 +206,232 @@ pub trait PromptItem: Sized {
     /// default.
     ///
     /// [`Self`]: Self
+    /// [`assume_yes`]: assume_yes
     /// [`std::io::stdin`]: io::stdin
     fn from_prompt(prompt: impl AsRef<str>, suffix: Option<char>) -> Result<Self> {
-        match suffix {
-            Some(c) => print!("{} [{}] {} ", prompt.as_ref(), Self::OPTIONS, c),
-            None => print!("{} [{}] ", prompt.as_ref(), Self::OPTIONS),
+        if assume_yes() {
+            return Self::assumed();
         }
 
-        let mut input = String::new();
-        io::stdout().flush().map_err(|_| Error)?;
-        io::stdin().read_line(&mut input).map_err(|_| Error)?;
-        Self::parse_input(input)
+        print_prompt(prompt.as_ref(), Self::options(), suffix);
+        Self::parse_input(read_input()?)
+    }
+
+    /// Outputs a prompt to the user and waits for input like [`from_prompt`]
+    /// if standard input is a terminal, otherwise answers the prompt as given
+    /// by `non_interactive` without reading any input.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if one is encountered when reading
+    /// from [`std::io::stdin`], if parsing fails and cannot provide a default,
+    /// or if standard input is not a terminal and the prompt cannot be
+    /// answered without it.
+    ///
+    /// [`from_prompt`]: PromptItem::from_prompt
+    /// [`std::io::stdin`]: io::stdin
+    fn from_prompt_or(
+        prompt: impl AsRef<str>,
+        suffix: Option<char>,
+        non_interactive: NonInteractive,
+    ) -> Result<Self> {
+        if assume_yes() || is_interactive() {
+            return Self::from_prompt(prompt, suffix);
+        }
+
+        Self::answer_non_interactive(non_interactive)
+    }
+
+    /// Answers a prompt as given by `non_interactive`, for when standard input
+    /// is not a terminal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prompt cannot be answered without input.
+    fn answer_non_interactive(non_interactive: NonInteractive) -> Result<Self> {
+        match non_interactive {
+            NonInteractive::Default => Self::parse_input(String::new()),
+            NonInteractive::AssumeYes => Self::assumed_yes().ok_or(Error::NotInteractive),
+            NonInteractive::Fail => Err(Error::NotInteractive),
+        }
+    }
+
+    /// Outputs a prompt to the user and waits for input, accepting only input
+    /// recognized by [`parse_strict`] rather than falling back to a default.
+    /// Unrecognized input is explained and the user is prompted again, up to
+    /// [`STRICT_ATTEMPTS`] times. This should be used for confirmations before
+    /// overwriting or deleting files. If standard input is not a terminal the
+    /// prompt is answered as given by `non_interactive`.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if one is encountered when reading
+    /// from [`std::io::stdin`], if input has ended, if standard input is not a
+    /// terminal and the prompt cannot be answered without it, or if no
+    /// recognized answer was given in [`STRICT_ATTEMPTS`] attempts.
+    ///
+    /// [`parse_strict`]: PromptItem::parse_strict
+    /// [`STRICT_ATTEMPTS`]: STRICT_ATTEMPTS
+    /// [`std::io::stdin`]: io::stdin
+    fn from_prompt_strict(
+        prompt: impl AsRef<str>,
+        suffix: Option<char>,
+        non_interactive: NonInteractive,
+    ) -> Result<Self> {
+        if assume_yes() {
+            return Self::assumed_yes().ok_or(Error::NotInteractive);
+        }
+
+        if !is_interactive() {
+            return Self::answer_non_interactive(non_interactive);
+        }
+
+        for _ in 0..STRICT_ATTEMPTS {
+            print_prompt(prompt.as_ref(), Self::options(), suffix);
+            let input = read_input()?;
+
+            // Reading nothing, not even a newline, means input has ended.
+            if input.is_empty() {
+                return Err(Error::Input);
+            }
+
+            match Self::parse_strict(&input) {
+                Some(item) => return Ok(item),
+                None => println!(
+                    "{}",
+                    Style::Failure.paint(Message::AnswerOneOf.format(&[&Self::options()]))
+                ),
+            }
+        }
+
+        Err(Error::TooManyAttempts)
+    }
+
+    /// Outputs a prompt showing the given default in place of the usual
+    /// options and waits for input, blank input accepts the default while
+    /// anything else is used instead of it. Input which cannot be parsed is
+    /// explained and the user is prompted again. If standard input is not a
+    /// terminal the default is used without prompting.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if one is encountered when reading
+    /// from [`std::io::stdin`], if input has ended, or if the default cannot
+    /// be parsed when standard input is not a terminal.
+    ///
+    /// [`std::io::stdin`]: io::stdin
+    fn from_prompt_with_default(
+        prompt: impl AsRef<str>,
+        suffix: Option<char>,
+        default: impl AsRef<str>,
+    ) -> Result<Self> {
+        Self::from_prompt_with_default_validated(prompt, suffix, default, |_| Ok(()))
+    }
+
+    /// Outputs a prompt showing the given default like
+    /// [`from_prompt_with_default`], then checks the created [`Self`] with the
+    /// given validation function like [`from_prompt_validated`]. If standard
+    /// input is not a terminal the default is used without prompting, so long
+    /// as it is valid.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if one is encountered when reading
+    /// from [`std::io::stdin`], if input has ended, or if the default cannot
+    /// be parsed or is invalid when standard input is not a terminal.
+    ///
+    /// [`from_prompt_with_default`]: PromptItem::from_prompt_with_default
+    /// [`from_prompt_validated`]: PromptItem::from_prompt_validated
+    /// [`Self`]: Self
+    /// [`std::io::stdin`]: io::stdin
+    fn from_prompt_with_default_validated(
+        prompt: impl AsRef<str>,
+        suffix: Option<char>,
+        default: impl AsRef<str>,
+        validate: impl Fn(&Self) -> result::Result<(), String>,
+    ) -> Result<Self> {
+        if assume_yes() || !is_interactive() {
+            return Self::parse_input(default.as_ref().to_owned())
+                .ok()
+                .filter(|item| validate(item).is_ok())
+                .ok_or(Error::Input);
+        }
+
+        loop {
+            print_prompt(prompt.as_ref(), default.as_ref(), suffix);
+            let input = read_input()?;
+
+            // Reading nothing, not even a newline, means input has ended.
+            if input.is_empty() {
+                return Err(Error::Input);
+            }
+
+            let input = match input.trim().is_empty() {
+                true => default.as_ref().to_owned(),
+                false => input,
+            };
+
+            let reason = match Self::parse_input(input) {
+                Ok(item) => match validate(&item) {
+                    Ok(()) => return Ok(item),
+                    Err(reason) => reason,
+                },
+                Err(_) => Message::EnterValid.format(&[&Self::options()]),
+            };
+
+            println!("{}", Style::Failure.paint(reason));
+        }
+    }
+
+    /// Outputs a prompt to the user and waits for input like [`from_prompt`],
+    /// then checks the created [`Self`] with the given validation function. If
+    /// it is invalid the reason given by the validation function is shown and
+    /// the user is prompted again, as they are if their input cannot be parsed.
+    ///
+    /// # Errors
+    ///
+    /// This function may return an error if one is encountered when reading
+    /// from [`std::io::stdin`], if input has ended, or if standard input is
+    /// not a terminal.
+    ///
+    /// [`from_prompt`]: PromptItem::from_prompt
+    /// [`Self`]: Self
+    /// [`std::io::stdin`]: io::stdin
+    fn from_prompt_validated(
+        prompt: impl AsRef<str>,
+        suffix: Option<char>,
+        validate: impl Fn(&Self) -> result::Result<(), String>,
+    ) -> Result<Self> {
+        if assume_yes() {
+            return Self::assumed()
+                .ok()
+                .filter(|item| validate(item).is_ok())
+                .ok_or(Error::NotInteractive);
+        }
+
+        if !is_interactive() {
+            return Err(Error::NotInteractive);
+        }
+
+        loop {
+            print_prompt(prompt.as_ref(), Self::options(), suffix);
+            let input = read_input()?;
+
+            // Reading nothing, not even a newline, means input has ended.
+            if input.is_empty() {
+                return Err(Error::Input);
+            }
+
+            let reason = match Self::parse_input(input) {
+                Ok(item) => match validate(&item) {
+                    Ok(()) => return Ok(item),
+                    Err(reason) => reason,
+                },
+                Err(_) => Message::EnterValid.format(&[&Self::options()]),
+            };
+
+            println!("{}", Style::Failure.paint(reason));
+        }
     }
 
     /// Given an input [`String`], returns a [`PromptItem`]. Should return a
@@ -99,6 +445,366 @@ pub trait PromptItem: Sized {
     /// [`String`]: String
     /// [`PromptItem`]: PromptItem
     fn parse_input(input: String) -> Result<Self>;
+
+    /// Parses the given input, returning [`None`] rather than a default if it
+    /// is not clearly recognized. By default any input [`parse_input`] accepts
+    /// is recognized.
+    ///
+    /// [`None`]: None
+    /// [`parse_input`]: PromptItem::parse_input
+    fn parse_strict(input: &str) -> Option<Self> {
+        Self::parse_input(input.to_owned()).ok()
+    }
+
+    /// Gets the [`PromptItem`] representing a "yes" answer, if there is one,
+    /// for use when prompts are answered without input.
+    ///
+    /// [`PromptItem`]: PromptItem
+    fn assumed_yes() -> Option<Self> {
+        None
+    }
+
+    /// Answers a prompt without input, giving the "yes" answer if there is one
+    /// and the default answer otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no "yes" answer and blank input cannot be
+    /// parsed.
+    fn assumed() -> Result<Self> {
+        match Self::assumed_yes() {
+            Some(item) => Ok(item),
+            None => Self::parse_input(String::new()),
+        }
+    }
+}
+
+/// A prompt for choosing one of a list of options, which are presented to the
+/// user as a numbered list, optionally with one chosen by default.
+#[derive(Debug, Clone, Copy)]
+pub struct Select<'a, T> {
+    options: &'a [T],
+    default: Option<usize>,
+}
+
+impl<'a, T> Select<'a, T>
+where
+    T: Display,
+{
+    /// Creates a new [`Select`] prompt for choosing one of the given options.
+    ///
+    /// [`Select`]: Select
+    #[inline]
+    #[must_use]
+    pub fn new(options: &'a [T]) -> Self {
+        Self {
+            options,
+            default: None,
+        }
+    }
+
+    /// Makes blank input choose the option at the given index, which is also
+    /// chosen without prompting under `--yes` or when standard input is not a
+    /// terminal.
+    #[inline]
+    #[must_use]
+    pub fn with_default(mut self, index: usize) -> Self {
+        self.default = Some(index);
+        self
+    }
+
+    /// Outputs the prompt followed by the numbered options and waits for the
+    /// user to choose one, returning its index. Invalid input is explained and
+    /// the user is prompted again. The default option, if any, is chosen
+    /// without prompting under `--yes` or if standard input is not a terminal.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if there are no options to choose from,
+    /// if the prompt cannot be shown and there is no default, or if one is
+    /// encountered when reading from [`std::io::stdin`].
+    ///
+    /// [`std::io::stdin`]: io::stdin
+    pub fn prompt(&self, prompt: impl AsRef<str>) -> Result<usize> {
+        if self.options.is_empty() {
+            return Err(Error::Input);
+        }
+
+        if assume_yes() || !is_interactive() {
+            return self.default.ok_or(Error::NoDefault);
+        }
+
+        println!("{}", Style::Prompt.paint(prompt.as_ref()));
+
+        for (i, option) in self.options.iter().enumerate() {
+            println!("    {}) {}", i + 1, option);
+        }
+
+        let select = match self.default {
+            Some(i) => Message::SelectOneOrDefault.format(&[&self.options.len(), &(i + 1)]),
+            None => Message::SelectOne.format(&[&self.options.len()]),
+        };
+
+        loop {
+            print!("{} ", select);
+            let input = read_input()?;
+
+            // Reading nothing, not even a newline, means input has ended.
+            if input.is_empty() {
+                return Err(Error::Input);
+            }
+
+            match self.parse_input(&input) {
+                Some(i) => return Ok(i),
+                None => println!(
+                    "{}",
+                    Style::Failure.paint(Message::EnterNumber.format(&[&self.options.len()]))
+                ),
+            }
+        }
+    }
+
+    /// Parses the given input as the number of an option, returning the index
+    /// of that option, or of the default option for blank input. Returns
+    /// [`None`] if the input does not choose any option.
+    ///
+    /// [`None`]: None
+    #[must_use]
+    pub fn parse_input(&self, input: &str) -> Option<usize> {
+        if input.trim().is_empty() {
+            return self.default;
+        }
+
+        input
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=self.options.len()).contains(n))
+            .map(|n| n - 1)
+    }
+}
+
+/// A prompt for choosing any number of a list of options, which are presented
+/// to the user as a numbered list. Options are chosen by their numbers or by
+/// ranges of numbers (e.g. "1 3-5"), 'a' chooses all options and 'n' chooses
+/// none.
+#[derive(Debug, Clone, Copy)]
+pub struct MultiSelect<'a, T> {
+    options: &'a [T],
+    all_by_default: bool,
+}
+
+impl<'a, T> MultiSelect<'a, T>
+where
+    T: Display,
+{
+    /// Creates a new [`MultiSelect`] prompt for choosing from the given options.
+    ///
+    /// [`MultiSelect`]: MultiSelect
+    #[inline]
+    #[must_use]
+    pub fn new(options: &'a [T]) -> Self {
+        Self {
+            options,
+            all_by_default: false,
+        }
+    }
+
+    /// Makes blank input choose every option, rather than none of them.
+    #[inline]
+    #[must_use]
+    pub fn all_by_default(mut self) -> Self {
+        self.all_by_default = true;
+        self
+    }
+
+    /// Outputs the prompt followed by the numbered options and waits for the
+    /// user to choose from them, returning the indices of the chosen options
+    /// in ascending order. Invalid input is explained and the user is
+    /// prompted again. If standard input is not a terminal the default choice
+    /// is returned without prompting.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if one is encountered when reading from
+    /// [`std::io::stdin`].
+    ///
+    /// [`std::io::stdin`]: io::stdin
+    pub fn prompt(&self, prompt: impl AsRef<str>) -> Result<Vec<usize>> {
+        if assume_yes() || !is_interactive() {
+            return Ok(self.parse_input("").unwrap_or_default());
+        }
+
+        println!("{}", Style::Prompt.paint(prompt.as_ref()));
+
+        for (i, option) in self.options.iter().enumerate() {
+            println!("    {}) {}", i + 1, option);
+        }
+
+        let default = match self.all_by_default {
+            true => Message::All.text(),
+            false => Message::None.text(),
+        };
+
+        loop {
+            print!("{} ", Message::SelectMany.format(&[&default]));
+
+            let input = read_input()?;
+
+            // Reading nothing, not even a newline, means input has ended.
+            if input.is_empty() {
+                return Err(Error::Input);
+            }
+
+            match self.parse_input(&input) {
+                Some(chosen) => return Ok(chosen),
+                None => println!(
+                    "{}",
+                    Style::Failure
+                        .paint(Message::EnterNumbersOrRanges.format(&[&self.options.len()]))
+                ),
+            }
+        }
+    }
+
+    /// Parses the given input as numbers and ranges of options, returning the
+    /// sorted indices of the chosen options. Returns [`None`] if any part of
+    /// the input is not a valid number, range, or keyword.
+    ///
+    /// [`None`]: None
+    #[must_use]
+    pub fn parse_input(&self, input: &str) -> Option<Vec<usize>> {
+        let all = || (0..self.options.len()).collect();
+
+        match input.trim().to_lowercase().as_str() {
+            "" if self.all_by_default => return Some(all()),
+            "" | "n" | "none" => return Some(Vec::new()),
+            "a" | "all" => return Some(all()),
+            _ => (),
+        }
+
+        let number = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=self.options.len()).contains(n))
+        };
+
+        let mut chosen = Vec::new();
+
+        for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+            match part.split_once('-') {
+                _ if part.is_empty() => continue,
+                Some((start, end)) => chosen.extend((number(start)?..=number(end)?).map(|n| n - 1)),
+                None => chosen.push(number(part)? - 1),
+            }
+        }
+
+        chosen.sort_unstable();
+        chosen.dedup();
+        Some(chosen)
+    }
+}
+
+/// Validation for use with [`from_prompt_validated`] which requires that the
+/// input is not blank.
+///
+/// [`from_prompt_validated`]: PromptItem::from_prompt_validated
+pub fn non_empty<T: AsRef<OsStr>>(item: &T) -> result::Result<(), String> {
+    match item.as_ref().is_empty() {
+        true => Err(Message::InputBlank.text().to_owned()),
+        false => Ok(()),
+    }
+}
+
+/// Creates a validation for use with [`from_prompt_validated`] which requires
+/// that the input is within the given range.
+///
+/// [`from_prompt_validated`]: PromptItem::from_prompt_validated
+pub fn in_range<T, R>(range: R) -> impl Fn(&T) -> result::Result<(), String>
+where
+    T: PartialOrd + Display,
+    R: RangeBounds<T>,
+{
+    move |item| match range.contains(item) {
+        true => Ok(()),
+        false => Err(describe_range(item, &range)),
+    }
+}
+
+/// Describes why a value is outside of the given range, e.g. "0 is not at
+/// least 1".
+fn describe_range<T: Display>(item: &T, range: &impl RangeBounds<T>) -> String {
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+
+    match (range.start_bound(), range.end_bound()) {
+        (Included(s), Included(e)) => Message::NotFromTo.format(&[item, s, e]),
+        (Included(s), Excluded(e)) => Message::NotFromUpTo.format(&[item, s, e]),
+        (Included(s), Unbounded) => Message::NotAtLeast.format(&[item, s]),
+        (Excluded(s), Unbounded) => Message::NotGreaterThan.format(&[item, s]),
+        (Unbounded, Included(e)) => Message::NotAtMost.format(&[item, e]),
+        (Unbounded, Excluded(e)) => Message::NotLessThan.format(&[item, e]),
+        _ => Message::OutOfRange.format(&[item]),
+    }
+}
+
+/// Validation for use with [`from_prompt_validated`] which requires that the
+/// given path exists.
+///
+/// [`from_prompt_validated`]: PromptItem::from_prompt_validated
+pub fn exists<T: AsRef<Path>>(path: &T) -> result::Result<(), String> {
+    match path.as_ref().exists() {
+        true => Ok(()),
+        false => Err(Message::DoesNotExist.format(&[&path.as_ref().display()])),
+    }
+}
+
+/// Makes every prompt for the rest of the process answer itself without
+/// reading input, giving "yes" to yes or no prompts and the default answer to
+/// others. Prompts with neither fail with [`Error::NotInteractive`].
+///
+/// [`Error::NotInteractive`]: Error::NotInteractive
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// Returns true if prompts should answer themselves, either because
+/// [`set_assume_yes`] was called or because [`ASSUME_YES_VAR`] is set.
+///
+/// [`set_assume_yes`]: set_assume_yes
+/// [`ASSUME_YES_VAR`]: ASSUME_YES_VAR
+#[must_use]
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::Relaxed)
+        || env::var(ASSUME_YES_VAR).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Returns true if standard input is a terminal, and so prompts can be
+/// answered by the user.
+#[must_use]
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+/// Prints a prompt for a [`PromptItem`] with the given options string.
+///
+/// [`PromptItem`]: PromptItem
+fn print_prompt(prompt: &str, options: &str, suffix: Option<char>) {
+    match suffix {
+        Some(c) => print!("{} [{}] {} ", Style::Prompt.paint(prompt), options, c),
+        None => print!("{} [{}] ", Style::Prompt.paint(prompt), options),
+    }
+}
+
+/// Flushes standard output, so that a prompt printed without a newline is
+/// shown, and then reads a line from standard input.
+fn read_input() -> Result<String> {
+    let mut input = String::new();
+    io::stdout().flush().map_err(|_| Error::Input)?;
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|_| Error::Input)?;
+    Ok(input)
 }
 
 /// [`Result`] type alias for [`PromptItem`] structs.
@@ -109,12 +815,150 @@ pub type Result<T> = result::Result<T, Error>;
 
 /// An error for prompts.
 #[derive(Debug)]
-pub struct Error;
+pub enum Error {
+    /// Input could not be read or parsed.
+    Input,
+
+    /// Input is required but standard input is not a terminal.
+    NotInteractive,
+
+    /// No recognized input was given after several attempts.
+    TooManyAttempts,
+
+    /// The prompt cannot be shown and has no default to assume.
+    NoDefault,
+}
 
 impl error::Error for Error {}
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Error getting input or parsing it.")
+        match self {
+            Error::Input => write!(f, "Error getting input or parsing it."),
+            Error::NotInteractive => {
+                write!(f, "Input is required but standard input is not a terminal.")
+            }
+            Error::TooManyAttempts => write!(f, "No valid answer was given."),
+            Error::NoDefault => write!(
+                f,
+                "No answer can be assumed, give it as an argument instead."
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_test() {
+        let options = ["a", "b", "c"];
+        let select = Select::new(&options);
+
+        assert_eq!(select.parse_input("2\n"), Some(1));
+        assert_eq!(select.parse_input("0\n"), None);
+        assert_eq!(select.parse_input("4\n"), None);
+        assert_eq!(select.parse_input("b\n"), None);
+        assert_eq!(select.parse_input("\n"), None);
+
+        let select = select.with_default(2);
+        assert_eq!(select.parse_input(" \n"), Some(2));
+        assert_eq!(select.parse_input("1\n"), Some(0));
+        assert_eq!(select.parse_input("4\n"), None);
+    }
+
+    #[test]
+    fn text_test() {
+        assert_eq!(String::parse_input("a b \r\n".to_owned()).unwrap(), "a b ");
+        assert_eq!(
+            PathBuf::parse_input(" out/dir\n".to_owned()).unwrap(),
+            PathBuf::from("out/dir")
+        );
+        assert!(non_empty(&String::parse_input("\n".to_owned()).unwrap()).is_err());
+        assert!(non_empty(&"a".to_owned()).is_ok());
+        assert!(exists(&env::temp_dir()).is_ok());
+        assert!(exists(&env::temp_dir().join("whim-missing")).is_err());
+    }
+
+    #[test]
+    fn yes_no_test() {
+        let (y, n) = (
+            i18n::locale().yes_answers()[1],
+            i18n::locale().no_answers()[1],
+        );
+
+        for (input, yes, no) in [
+            ("\n".to_owned(), Yes::Yes, No::No),
+            ("n\n".to_owned(), Yes::No, No::No),
+            (format!("{}\r\n", n.to_uppercase()), Yes::No, No::No),
+            (format!(" {} \n", y.to_uppercase()), Yes::Yes, No::Yes),
+        ] {
+            assert!(Yes::parse_input(input.clone()).unwrap() == yes);
+            assert!(No::parse_input(input).unwrap() == no);
+        }
+
+        assert_eq!(answer("y\r\n", Locale::English), Some(true));
+        assert_eq!(answer(" Nein \n", Locale::German), Some(false));
+        assert_eq!(answer("ja", Locale::English), None);
+        assert_eq!(answer("yes", Locale::German), None);
+    }
+
+    #[test]
+    fn strict_test() {
+        let y = i18n::locale().yes_answers()[0];
+
+        assert!(Yes::parse_strict(&format!("{}\n", y)) == Some(Yes::Yes));
+        assert!(Yes::parse_strict("N\r\n") == Some(Yes::No));
+        assert!(Yes::parse_strict("\n").is_none());
+        assert!(No::parse_strict("nah\n").is_none());
+    }
+
+    #[test]
+    fn numeric_test() {
+        assert_eq!(u16::parse_input(" 8080\n".to_owned()).unwrap(), 8080);
+        assert_eq!(f64::parse_input("1.5\n".to_owned()).unwrap(), 1.5);
+        assert!(u16::parse_input("-1\n".to_owned()).is_err());
+        assert!(i32::parse_input("ten\n".to_owned()).is_err());
+
+        let page_size = in_range(1..=100);
+        assert!(page_size(&1).is_ok());
+        assert!(page_size(&100).is_ok());
+        assert_eq!(
+            page_size(&0),
+            Err(Message::NotFromTo.format(&[&0, &1, &100]))
+        );
+        assert_eq!(
+            in_range(1..)(&0),
+            Err(Message::NotAtLeast.format(&[&0, &1]))
+        );
+
+        set_assume_yes(true);
+        assert_eq!(
+            usize::from_prompt_with_default_validated("", None, "5", in_range(..=50)).unwrap(),
+            5
+        );
+        assert!(
+            usize::from_prompt_with_default_validated("", None, "51", in_range(..=50)).is_err()
+        );
+    }
+
+    #[test]
+    fn multi_select_test() {
+        let options = ["a", "b", "c", "d", "e"];
+        let select = MultiSelect::new(&options);
+
+        assert_eq!(select.parse_input("2 4\n"), Some(vec![1, 3]));
+        assert_eq!(select.parse_input("5, 1-3\n"), Some(vec![0, 1, 2, 4]));
+        assert_eq!(select.parse_input("2-3 3\n"), Some(vec![1, 2]));
+        assert_eq!(select.parse_input("a\n"), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(select.parse_input("n\n"), Some(vec![]));
+        assert_eq!(select.parse_input("\n"), Some(vec![]));
+        assert_eq!(
+            select.all_by_default().parse_input("\n").map(|c| c.len()),
+            Some(5)
+        );
+        assert_eq!(select.parse_input("6\n"), None);
+        assert_eq!(select.parse_input("1-x\n"), None);
     }
 }
@@ -0,0 +1,156 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Documents protected by a passphrase, given as `protect` in their front
+//! matter. The content of a protected document's page is encrypted as the
+//! site is built, with AES-GCM and a key derived from the passphrase by
+//! PBKDF2, and the page instead asks readers for the passphrase, decrypting
+//! its content in the browser. A static host then only ever serves the
+//! encrypted content.
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use sha2::Sha256;
+use std::{error, fmt, result};
+
+/// The href of the [`SCRIPT`], relative to the root of the output directory.
+///
+/// [`SCRIPT`]: SCRIPT
+pub const SCRIPT_HREF: &str = "protect.js";
+
+/// Script loaded by protected pages, it decrypts their content with the
+/// passphrase given by the reader.
+pub const SCRIPT: &str = include_str!("../theme/protect.js");
+
+/// Rounds of PBKDF2 deriving keys from passphrases, slowing guesses at them.
+const ITERATIONS: u32 = 600_000;
+
+/// Length, in bytes, of the salt keys are derived with.
+const SALT_LEN: usize = 16;
+
+/// Length, in bytes, of the nonce content is encrypted with.
+const NONCE_LEN: usize = 12;
+
+/// Content encrypted with a passphrase, and what is needed to decrypt it
+/// given the same passphrase. Bytes are encoded as base64.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Locked {
+    salt: String,
+    nonce: String,
+    iterations: u32,
+    ciphertext: String,
+}
+
+/// Encrypts the given content with the given passphrase, with a new random
+/// salt and nonce.
+///
+/// # Errors
+///
+/// Returns an error if the content could not be encrypted.
+pub fn encrypt(content: &str, passphrase: &str) -> Result<Locked> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    OsRng
+        .try_fill_bytes(&mut salt)
+        .map_err(|_| Error::RandomError)?;
+    OsRng
+        .try_fill_bytes(&mut nonce)
+        .map_err(|_| Error::RandomError)?;
+
+    encrypt_with(content, passphrase, &salt, &nonce, ITERATIONS)
+}
+
+/// Encrypts the given content with the given passphrase, salt, nonce, and
+/// number of PBKDF2 rounds.
+fn encrypt_with(
+    content: &str,
+    passphrase: &str,
+    salt: &[u8],
+    nonce: &[u8],
+    iterations: u32,
+) -> Result<Locked> {
+    let key = pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(passphrase.as_bytes(), salt, iterations);
+    let ciphertext = Aes256Gcm::new(&key.into())
+        .encrypt(Nonce::from_slice(nonce), content.as_bytes())
+        .map_err(|_| Error::EncryptionError)?;
+
+    Ok(Locked {
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        iterations,
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+/// Creates the content of a protected page from its [`Locked`] content and
+/// the href of the [`SCRIPT`] from the page.
+///
+/// [`Locked`]: Locked
+/// [`SCRIPT`]: SCRIPT
+#[must_use]
+pub fn content(locked: &Locked, script: &str) -> String {
+    format!(
+        concat!(
+            r#"<form class="protected" id="protected">"#,
+            r#"<p>This page is protected.</p>"#,
+            r#"<input type="password" id="passphrase" placeholder="Passphrase" aria-label="Passphrase" required autofocus> "#,
+            r#"<button type="submit">Unlock</button>"#,
+            r#"<p id="protected-error" hidden>Wrong passphrase.</p>"#,
+            r#"</form>"#,
+            r#"<script type="application/json" id="protected-data">{}</script>"#,
+            r#"<script src="{}"></script>"#,
+        ),
+        // Base64 and numbers cannot close the script element.
+        serde_json::to_string(locked).unwrap_or_default(),
+        script
+    )
+}
+
+/// The result type of page protection related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while protecting a page.
+#[derive(Debug)]
+pub enum Error {
+    /// The system could not provide random bytes for a salt or nonce.
+    RandomError,
+
+    /// The content could not be encrypted.
+    EncryptionError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_test() {
+        let (salt, nonce) = ([1; SALT_LEN], [2; NONCE_LEN]);
+        let locked = encrypt_with("<p>Secret</p>", "hunter2", &salt, &nonce, 1000).unwrap();
+
+        let key = pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(b"hunter2", &salt, 1000);
+        let decrypted = Aes256Gcm::new(&key.into())
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                BASE64.decode(&locked.ciphertext).unwrap().as_slice(),
+            )
+            .unwrap();
+
+        assert_eq!(decrypted, b"<p>Secret</p>");
+        assert_eq!(locked.salt, BASE64.encode(salt));
+        assert!(!content(&locked, "protect.js").contains("Secret"));
+    }
+}
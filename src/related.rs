@@ -0,0 +1,146 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Related documents, listed at the bottom of each document page. Documents
+//! are related by the tags in their front matter, the links they share, and
+//! the words their titles have in common, in that order of weight.
+
+use crate::{escape, href};
+use build_html::{self as html, HtmlContainer};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+/// Number of related documents listed if the site does not set it.
+pub const DEFAULT_COUNT: usize = 5;
+
+/// Weight of each tag two documents share.
+const TAG_WEIGHT: usize = 3;
+
+/// Weight of each link two documents share.
+const LINK_WEIGHT: usize = 2;
+
+/// Title words shorter than this are not compared, skipping most articles and
+/// conjunctions.
+const MIN_TERM_LEN: usize = 4;
+
+/// What a document is compared to others by.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    tags: BTreeSet<String>,
+    links: BTreeSet<String>,
+    terms: BTreeSet<String>,
+}
+
+impl Profile {
+    /// Creates a new [`Profile`] of the document at the given path with the
+    /// given title, tags, and link destinations. Tags and title words are
+    /// compared ignoring case, and links are compared without fragments once
+    /// relative ones are resolved against the document's directory.
+    ///
+    /// [`Profile`]: Profile
+    #[must_use]
+    pub fn new(path: &str, title: &str, tags: &[String], links: &[String]) -> Self {
+        let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+
+        Self {
+            tags: tags.iter().map(|tag| tag.trim().to_lowercase()).collect(),
+            links: links
+                .iter()
+                .map(|link| link.split('#').next().unwrap_or_default())
+                .filter(|link| !link.is_empty())
+                .map(|link| match link.contains(':') || link.starts_with('/') {
+                    true => link.to_owned(),
+                    false => href::from_path(&dir.join(link)),
+                })
+                .collect(),
+            terms: title
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|term| term.chars().count() >= MIN_TERM_LEN)
+                .map(str::to_lowercase)
+                .collect(),
+        }
+    }
+
+    /// Scores how related this [`Profile`] is to another, zero being not at
+    /// all.
+    ///
+    /// [`Profile`]: Profile
+    #[must_use]
+    pub fn score(&self, other: &Profile) -> usize {
+        self.tags.intersection(&other.tags).count() * TAG_WEIGHT
+            + self.links.intersection(&other.links).count() * LINK_WEIGHT
+            + self.terms.intersection(&other.terms).count()
+    }
+}
+
+/// Finds up to `count` documents related to the one of the given key, most
+/// related first, from a map of each document's key to its [`Profile`].
+///
+/// [`Profile`]: Profile
+#[must_use]
+pub fn related<'a>(profiles: &'a BTreeMap<&str, Profile>, key: &str, count: usize) -> Vec<&'a str> {
+    let profile = match profiles.get(key) {
+        Some(profile) => profile,
+        None => return Vec::new(),
+    };
+
+    let mut scores = profiles
+        .iter()
+        .filter(|(other, _)| **other != key)
+        .map(|(other, p)| (profile.score(p), *other))
+        .filter(|(score, _)| *score > 0)
+        .collect::<Vec<_>>();
+
+    // Stable, so equally related documents stay in order of their keys.
+    scores.sort_by(|(a, _), (b, _)| b.cmp(a));
+    scores.into_iter().take(count).map(|(_, key)| key).collect()
+}
+
+/// Creates the list of related documents shown on a document page from the
+/// given tuples of each document's href and name.
+#[must_use]
+pub fn content<'a>(docs: impl IntoIterator<Item = (String, &'a str)>) -> html::Container {
+    html::Container::new(html::ContainerType::Nav)
+        .with_attributes(vec![("class", "related")])
+        .with_header(2, "Related")
+        .with_container(docs.into_iter().fold(
+            html::Container::new(html::ContainerType::UnorderedList),
+            |list, (href, name)| list.with_link(href, escape::html(name)),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn related_test() {
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let profiles = BTreeMap::from([
+            (
+                "a.md",
+                Profile::new("a.md", "Rust Notes", &tags(&["Rust"]), &[]),
+            ),
+            (
+                "b.md",
+                Profile::new("b.md", "Cooking", &tags(&["rust "]), &tags(&["x.html#top"])),
+            ),
+            (
+                "c.md",
+                Profile::new("c.md", "More rust notes", &[], &tags(&["blog/../x.html"])),
+            ),
+            (
+                "blog/d.md",
+                Profile::new("blog/d.md", "Gardening", &[], &tags(&["x.html", "#top"])),
+            ),
+        ]);
+
+        assert_eq!(related(&profiles, "a.md", 5), ["b.md", "c.md"]);
+        assert_eq!(related(&profiles, "b.md", 1), ["a.md"]);
+        assert_eq!(related(&profiles, "c.md", 5), ["a.md", "b.md"]);
+        assert!(related(&profiles, "blog/d.md", 5).is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! User scripts, written in [Rhai](https://rhai.rs), which transform each
+//! document's page as the site is generated. A script defines a `transform`
+//! function taking a map of the page's `path`, `href`, `title`, `lang`, and
+//! rendered HTML `content`, along with the site's [data] as `data`, and
+//! returning the map, with the title and content changed as it likes. Scripts
+//! run in the order they are listed in the configuration, each being given the
+//! page as left by the one before.
+//!
+//! Scripting is only available when whim is built with the `scripting`
+//! feature, otherwise configured scripts are ignored with a warning.
+//!
+//! [data]: crate::data
+
+use crate::data::Data;
+use std::{error, fmt, path::PathBuf, result};
+
+/// Name of the function scripts define to transform pages.
+#[cfg(feature = "scripting")]
+const TRANSFORM_FN: &str = "transform";
+
+/// The context of a document's page which scripts are given.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Page {
+    /// Path of the document's markdown file.
+    pub path: String,
+
+    /// Href of the page, relative to the root of the output directory.
+    pub href: String,
+
+    /// Title of the page, may be changed by scripts.
+    pub title: String,
+
+    /// Language the page is in, if the site has several.
+    pub lang: Option<String>,
+
+    /// The document rendered to HTML, may be changed by scripts.
+    pub content: String,
+}
+
+/// Compiled scripts, see the [module] documentation.
+///
+/// [module]: crate::scripts
+#[derive(Default)]
+pub struct Scripts {
+    #[cfg(feature = "scripting")]
+    engine: rhai::Engine,
+
+    #[cfg(feature = "scripting")]
+    scripts: Vec<(PathBuf, rhai::AST)>,
+
+    /// The site's data, given to scripts with each page.
+    #[cfg(feature = "scripting")]
+    data: rhai::Dynamic,
+}
+
+impl Scripts {
+    /// Compiles the scripts at the given paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a script cannot be read or compiled, or does not
+    /// define a `transform` function.
+    #[cfg(feature = "scripting")]
+    pub fn load(paths: &[PathBuf]) -> Result<Self> {
+        let engine = rhai::Engine::new();
+        let mut scripts = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let ast = engine.compile_file(path.clone()).map_err(|e| {
+                log::error!("could not compile '{}': {}", path.display(), e);
+                Error::CompileError
+            })?;
+
+            if !ast.iter_functions().any(|f| f.name == TRANSFORM_FN) {
+                log::error!(
+                    "'{}' does not define a `{}` function",
+                    path.display(),
+                    TRANSFORM_FN
+                );
+                return Err(Error::MissingTransform);
+            }
+
+            scripts.push((path.clone(), ast));
+        }
+
+        Ok(Self {
+            engine,
+            scripts,
+            data: rhai::Dynamic::UNIT,
+        })
+    }
+
+    /// Warns that the scripts at the given paths are ignored, since whim was
+    /// built without the `scripting` feature.
+    ///
+    /// # Errors
+    ///
+    /// Never returns an error, though it may when built with scripting.
+    #[cfg(not(feature = "scripting"))]
+    pub fn load(paths: &[PathBuf]) -> Result<Self> {
+        if !paths.is_empty() {
+            log::warn!(
+                "ignoring {} scripts, whim was built without the `scripting` feature",
+                paths.len()
+            );
+        }
+
+        Ok(Self::default())
+    }
+
+    /// Gives scripts the given [`Data`] with each page.
+    ///
+    /// [`Data`]: Data
+    #[cfg(feature = "scripting")]
+    #[must_use]
+    pub fn with_data(self, data: &Data) -> Self {
+        let files = data
+            .files()
+            .iter()
+            .map(|(name, value)| (name.into(), to_dynamic(value)))
+            .collect::<rhai::Map>();
+
+        Self {
+            data: files.into(),
+            ..self
+        }
+    }
+
+    /// Returns the scripts as they are, since whim was built without the
+    /// `scripting` feature.
+    #[cfg(not(feature = "scripting"))]
+    #[inline]
+    #[must_use]
+    pub fn with_data(self, _data: &Data) -> Self {
+        self
+    }
+
+    /// Passes the given [`Page`] through the `transform` function of each
+    /// script in turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a script fails or does not return a map.
+    ///
+    /// [`Page`]: Page
+    #[cfg(feature = "scripting")]
+    pub fn transform(&self, page: Page) -> Result<Page> {
+        self.scripts.iter().try_fold(page, |page, (path, ast)| {
+            let mut map = rhai::Map::new();
+            map.insert("path".into(), page.path.clone().into());
+            map.insert("href".into(), page.href.clone().into());
+            map.insert("title".into(), page.title.clone().into());
+            map.insert(
+                "lang".into(),
+                page.lang.clone().map_or(rhai::Dynamic::UNIT, Into::into),
+            );
+            map.insert("content".into(), page.content.clone().into());
+            map.insert("data".into(), self.data.clone());
+
+            let result: rhai::Dynamic = self
+                .engine
+                .call_fn(&mut rhai::Scope::new(), ast, TRANSFORM_FN, (map,))
+                .map_err(|e| {
+                    log::error!("'{}' failed on '{}': {}", path.display(), page.path, e);
+                    Error::ScriptError
+                })?;
+
+            let map = result.try_cast::<rhai::Map>().ok_or_else(|| {
+                log::error!(
+                    "'{}' did not return a map for '{}'",
+                    path.display(),
+                    page.path
+                );
+                Error::ScriptError
+            })?;
+
+            let get = |key: &str| map.get(key).and_then(|v| v.clone().into_string().ok());
+
+            Ok(Page {
+                title: get("title").unwrap_or(page.title.clone()),
+                content: get("content").unwrap_or(page.content.clone()),
+                ..page
+            })
+        })
+    }
+
+    /// Returns the given [`Page`] as is, since whim was built without the
+    /// `scripting` feature.
+    ///
+    /// # Errors
+    ///
+    /// Never returns an error, though it may when built with scripting.
+    ///
+    /// [`Page`]: Page
+    #[cfg(not(feature = "scripting"))]
+    #[inline]
+    pub fn transform(&self, page: Page) -> Result<Page> {
+        Ok(page)
+    }
+}
+
+/// Converts a value of the site's data into one scripts may use.
+#[cfg(feature = "scripting")]
+fn to_dynamic(value: &serde_json::Value) -> rhai::Dynamic {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => rhai::Dynamic::UNIT,
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => n.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        Value::String(s) => s.clone().into(),
+        Value::Array(items) => items.iter().map(to_dynamic).collect::<rhai::Array>().into(),
+        Value::Object(table) => table
+            .iter()
+            .map(|(key, value)| (key.into(), to_dynamic(value)))
+            .collect::<rhai::Map>()
+            .into(),
+    }
+}
+
+impl fmt::Debug for Scripts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Scripts");
+
+        #[cfg(feature = "scripting")]
+        debug.field(
+            "scripts",
+            &self.scripts.iter().map(|(p, _)| p).collect::<Vec<_>>(),
+        );
+
+        debug.finish()
+    }
+}
+
+/// The result type of scripting related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while loading or running scripts.
+#[derive(Debug)]
+pub enum Error {
+    /// A script could not be read or has a syntax error.
+    #[cfg(feature = "scripting")]
+    CompileError,
+
+    /// A script does not define a `transform` function.
+    #[cfg(feature = "scripting")]
+    MissingTransform,
+
+    /// A script failed while transforming a page.
+    #[cfg(feature = "scripting")]
+    ScriptError,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn transform_test() {
+        let dir = std::env::temp_dir().join("whim-scripts-test");
+        fs::create_dir_all(&dir).unwrap();
+
+        let banner = dir.join("banner.rhai");
+        let title = dir.join("title.rhai");
+        let broken = dir.join("broken.rhai");
+
+        fs::write(
+            &banner,
+            r#"fn transform(page) {
+                page.content = "<p>draft</p>" + page.content;
+                page.path = "ignored";
+                page
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            &title,
+            r#"fn transform(page) { page.title += " | " + page.href; page }"#,
+        )
+        .unwrap();
+        fs::write(&broken, "let x = 1;").unwrap();
+
+        let scripts = Scripts::load(&[banner, title]).unwrap();
+        let page = scripts
+            .transform(Page {
+                path: "a.md".to_owned(),
+                href: "a.html".to_owned(),
+                title: "A".to_owned(),
+                lang: None,
+                content: "<h1>A</h1>".to_owned(),
+            })
+            .unwrap();
+
+        assert_eq!(page.path, "a.md");
+        assert_eq!(page.title, "A | a.html");
+        assert_eq!(page.content, "<p>draft</p><h1>A</h1>");
+        assert!(matches!(
+            Scripts::load(&[broken]),
+            Err(Error::MissingTransform)
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Generation of a client side search index and the page that searches it,
+//! allowing a generated site to be searched without any server.
+
+use crate::md_content::MdContent;
+use build_html::{self as html, HtmlContainer};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// The href of the generated search index.
+pub const INDEX_HREF: &str = "search-index.json";
+
+/// The href of the generated search page.
+pub const PAGE_HREF: &str = "search.html";
+
+/// Maximum length, in characters, of an entry's excerpt.
+const EXCERPT_LENGTH: usize = 200;
+
+/// The href of the [`SCRIPT`], relative to the root of the output directory.
+///
+/// [`SCRIPT`]: SCRIPT
+pub const SCRIPT_HREF: &str = "search.js";
+
+/// Script loaded by the search page, it fetches the search index and lists
+/// entries matching every term given in the search box.
+pub const SCRIPT: &str = include_str!("../theme/search.js");
+
+/// A single document's entry in the search index.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchEntry {
+    title: String,
+    href: String,
+    excerpt: String,
+
+    /// Every distinct word of the document, lowercased, only present if the
+    /// full body of documents is being indexed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<BTreeSet<String>>,
+}
+
+impl SearchEntry {
+    /// Creates a new [`SearchEntry`] for the given [`MdContent`] which will be
+    /// found at the given href. The document's body is only tokenized and
+    /// included if `with_body` is true.
+    ///
+    /// [`SearchEntry`]: SearchEntry
+    /// [`MdContent`]: MdContent
+    #[must_use]
+    pub fn new(md: &MdContent, href: impl Into<String>, with_body: bool) -> Self {
+        Self {
+            title: md.title().map(|t| t.to_string()).unwrap_or_default(),
+            href: href.into(),
+            excerpt: md.excerpt(EXCERPT_LENGTH),
+            body: with_body.then(|| tokenize(&md.text())),
+        }
+    }
+}
+
+/// Serializes the given [`SearchEntry`] items into a JSON search index.
+///
+/// [`SearchEntry`]: SearchEntry
+#[must_use]
+pub fn index_json(entries: &[SearchEntry]) -> String {
+    // Serializing plain strings and sets to JSON cannot fail.
+    serde_json::to_string(entries).unwrap_or_default()
+}
+
+/// Creates the content of the search page, which must be written to the root
+/// of the output directory alongside the search index.
+#[must_use]
+pub fn content() -> html::Container {
+    html::Container::new(html::ContainerType::Div)
+        .with_attributes(vec![("class", "search")])
+        .with_header(1, "Search")
+        .with_raw(
+            r#"<input type="search" id="search" placeholder="Search..." aria-label="Search" autofocus>"#,
+        )
+        .with_container(
+            html::Container::new(html::ContainerType::UnorderedList)
+                .with_attributes(vec![("id", "results"), ("aria-live", "polite")]),
+        )
+        .with_raw(format!(r#"<script src="{}"></script>"#, SCRIPT_HREF))
+}
+
+/// Splits the given text into a set of distinct, lowercased, words.
+#[must_use]
+fn tokenize(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_test() {
+        let tokens = tokenize("Hello, world! hello again... (world)");
+        let expected = ["again", "hello", "world"];
+
+        assert_eq!(tokens.len(), expected.len());
+        assert!(expected.iter().all(|w| tokens.contains(*w)));
+    }
+
+    #[test]
+    fn entry_test() {
+        let md = MdContent::new("# Title\n\nSome *first* paragraph.\n\nAnother one.");
+        let entry = SearchEntry::new(&md, "doc.html", false);
+
+        assert_eq!(entry.title, "Title");
+        assert_eq!(entry.excerpt, "Some first paragraph.");
+        assert_eq!(entry.body, None);
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Series of documents meant to be read in order. Documents join a series by
+//! naming it in their front matter, optionally giving their `part` in it, and
+//! each series is given an index page listing its parts.
+
+use crate::{escape, href};
+use build_html::{self as html, HtmlContainer};
+use std::collections::BTreeMap;
+
+/// Directory series index pages are written to.
+const DIR: &str = "series";
+
+/// A document's membership of a series, see [`collect`].
+///
+/// [`collect`]: collect
+#[derive(Clone, Debug, PartialEq)]
+pub struct Member<'a> {
+    pub series: &'a str,
+    pub part: Option<u32>,
+    pub date: time::OffsetDateTime,
+    pub key: &'a str,
+}
+
+/// Gets the href of the index page of the series of the given name.
+#[must_use]
+pub fn href(name: &str) -> String {
+    format!("{}/{}.html", DIR, href::slugify_name(name))
+}
+
+/// Collects the given members into the keys of each series' documents in
+/// order, keyed by the series' name. Documents are ordered by part, those
+/// without a part following in order of date.
+#[must_use]
+pub fn collect<'a>(
+    members: impl IntoIterator<Item = Member<'a>>,
+) -> BTreeMap<&'a str, Vec<&'a str>> {
+    let mut series: BTreeMap<&str, Vec<Member>> = BTreeMap::new();
+
+    for member in members {
+        series.entry(member.series).or_default().push(member);
+    }
+
+    series
+        .into_iter()
+        .map(|(name, mut members)| {
+            members.sort_by_key(|m| (m.part.unwrap_or(u32::MAX), m.date, m.key));
+            (name, members.into_iter().map(|m| m.key).collect())
+        })
+        .collect()
+}
+
+/// Creates the navigation shown on the page of a document in a series, given
+/// the series' name, the href of its index, the href and name of each of its
+/// documents in order, and the position of the document among them.
+#[must_use]
+pub fn navigation(
+    name: &str,
+    index: &str,
+    parts: &[(String, &str)],
+    position: usize,
+) -> html::Container {
+    let nav = html::Container::new(html::ContainerType::Nav)
+        .with_attributes(vec![("class", "series")])
+        .with_raw(format!(
+            "<p>Part {} of {} in <a href=\"{}\">{}</a></p>",
+            position + 1,
+            parts.len(),
+            index,
+            escape::html(name)
+        ));
+
+    let nav = match position.checked_sub(1).and_then(|i| parts.get(i)) {
+        Some((href, name)) => {
+            nav.with_link_attr(href, format!("← {}", escape::html(name)), [("rel", "prev")])
+        }
+        None => nav,
+    };
+
+    match parts.get(position + 1) {
+        Some((href, name)) => {
+            nav.with_link_attr(href, format!("{} →", escape::html(name)), [("rel", "next")])
+        }
+        None => nav,
+    }
+}
+
+/// Creates the content of a series' index page from its name and the href and
+/// name of each of its documents in order.
+#[must_use]
+pub fn content<'a>(
+    name: &str,
+    docs: impl IntoIterator<Item = (String, &'a str)>,
+) -> html::Container {
+    html::Container::new(html::ContainerType::Div)
+        .with_attributes(vec![("class", "series")])
+        .with_header(1, escape::html(name))
+        .with_container(docs.into_iter().fold(
+            html::Container::new(html::ContainerType::OrderedList),
+            |list, (href, name)| list.with_link(href, escape::html(name)),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_test() {
+        let date = |secs| time::OffsetDateTime::from_unix_timestamp(secs).unwrap();
+        let member = |series, part, secs, key| Member {
+            series,
+            part,
+            date: date(secs),
+            key,
+        };
+
+        let series = collect([
+            member("Rust", None, 2, "d.md"),
+            member("Rust", Some(2), 0, "b.md"),
+            member("Cooking", None, 0, "e.md"),
+            member("Rust", None, 1, "c.md"),
+            member("Rust", Some(1), 5, "a.md"),
+        ]);
+
+        assert_eq!(series["Rust"], ["a.md", "b.md", "c.md", "d.md"]);
+        assert_eq!(series["Cooking"], ["e.md"]);
+        assert_eq!(href("My Series"), "series/my-series.html");
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Links for sharing document pages, made without any JavaScript from the
+//! page's canonical URL and title. Only sites with a base URL can be shared.
+
+use crate::href;
+use build_html::{self as html, HtmlContainer};
+use serde::{Deserialize, Serialize};
+
+/// A service pages may be shared to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Service {
+    /// An email, opened in the reader's mail client.
+    Email,
+
+    /// A post on Mastodon, the reader being asked for their instance.
+    Mastodon,
+
+    /// A post on X.
+    X,
+
+    /// A post on Bluesky.
+    Bluesky,
+}
+
+impl Service {
+    /// Gets the link sharing the page of the given URL and title.
+    #[must_use]
+    pub fn link(self, url: &str, title: &str) -> String {
+        let (url, title) = (href::encode(url), href::encode(title));
+
+        match self {
+            Service::Email => format!("mailto:?subject={}&body={}", title, url),
+            Service::Mastodon => format!("https://mastodonshare.com/?text={}&url={}", title, url),
+            Service::X => format!("https://x.com/intent/tweet?text={}&url={}", title, url),
+            Service::Bluesky => format!("https://bsky.app/intent/compose?text={}%20{}", title, url),
+        }
+    }
+
+    /// Gets the name of the service as shown to readers.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Service::Email => "Email",
+            Service::Mastodon => "Mastodon",
+            Service::X => "X",
+            Service::Bluesky => "Bluesky",
+        }
+    }
+}
+
+/// Creates the share links of the page of the given URL and title.
+#[must_use]
+pub fn content(services: &[Service], url: &str, title: &str) -> html::Container {
+    services.iter().fold(
+        html::Container::new(html::ContainerType::Nav)
+            .with_attributes(vec![("class", "share"), ("aria-label", "Share")]),
+        |nav, service| {
+            nav.with_link_attr(
+                service.link(url, title),
+                service.label(),
+                vec![("rel", "noopener")],
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_test() {
+        let url = "https://example.com/my%20note.html";
+
+        assert_eq!(
+            Service::Email.link(url, "A & B"),
+            "mailto:?subject=A%20%26%20B&body=https%3A//example.com/my%2520note.html"
+        );
+        assert_eq!(
+            Service::Bluesky.link(url, "Notes"),
+            "https://bsky.app/intent/compose?text=Notes%20https%3A//example.com/my%2520note.html"
+        );
+    }
+}
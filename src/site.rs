@@ -0,0 +1,436 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+use crate::{
+    archive::ArchiveDate,
+    comments::Comments,
+    href::{self, Href},
+    images::Images,
+    related,
+    share::Service,
+};
+use serde::{Deserialize, Serialize};
+use std::{env, path::Path, rc::Rc};
+use time::format_description::{self, well_known::Rfc3339};
+
+/// Format of dates shown on generated pages if none has been set, see the
+/// [`time`] crate's format description documentation for the syntax.
+///
+/// [`time`]: time::format_description
+const DEFAULT_DATE_FORMAT: &str =
+    "[day padding:none] [month repr:long] [year], [hour repr:12 padding:none]:[minute] [period]";
+
+/// Number of recently updated documents listed on indexes if none has been
+/// set.
+const DEFAULT_RECENT: usize = 5;
+
+/// Most related or recently updated documents `whim new` accepts listing, as
+/// many more would make for long lists on every page.
+pub const MAX_LISTED: usize = 50;
+
+/// How links between generated pages are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkMode {
+    /// Links are relative to the page they are on, this works regardless of
+    /// where the site is hosted and when opening pages straight from disk.
+    #[default]
+    Relative,
+
+    /// Links are absolute, starting with the site's base URL if one is set or
+    /// otherwise with a '/'.
+    Absolute,
+}
+
+/// Site wide settings that apply to every generated page.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Site {
+    /// Title of the site, used for the index page.
+    title: Option<Rc<str>>,
+
+    /// Label of the link back to the index on every other page.
+    home_label: Option<Rc<str>>,
+
+    /// URL the site will be hosted at, e.g. "https://example.com/notes/".
+    base_url: Option<Rc<str>>,
+
+    /// Author of documents which do not name their own.
+    author: Option<Rc<str>>,
+
+    /// How links between generated pages are written.
+    link_mode: LinkMode,
+
+    /// Whether to slugify the hrefs of documents, e.g. writing "My Note.md"
+    /// to "my-note.html" rather than "My Note.html".
+    slug_hrefs: bool,
+
+    /// Language the site is written in, as a BCP 47 language tag.
+    lang: Option<Rc<str>>,
+
+    /// Languages the site is translated into, the first being the language of
+    /// documents not marked as being in any other. Empty for sites written in
+    /// a single language.
+    languages: Vec<Rc<str>>,
+
+    /// Path to a favicon, relative to the library's directory.
+    favicon: Option<Rc<str>>,
+
+    /// Path to an apple-touch-icon, relative to the library's directory.
+    touch_icon: Option<Rc<str>>,
+
+    /// Whether to include the full, tokenized, body of each document in the
+    /// search index rather than only its title and excerpt.
+    search_body: bool,
+
+    /// The date documents are grouped by on the archive page.
+    archive_by: ArchiveDate,
+
+    /// Format of dates shown on generated pages.
+    date_format: Option<Rc<str>>,
+
+    /// Whether to add a button to every page for switching between light and
+    /// dark color schemes.
+    theme_toggle: bool,
+
+    /// URL of the endpoint receiving webmentions of the site's pages.
+    webmention: Option<Rc<str>>,
+
+    /// URL of the endpoint receiving pingbacks of the site's pages.
+    pingback: Option<Rc<str>>,
+
+    /// Comment service embedded at the bottom of document pages.
+    comments: Option<Comments>,
+
+    /// Number of related documents listed on document pages.
+    related: Option<usize>,
+
+    /// Number of recently updated documents listed on indexes.
+    recent: Option<usize>,
+
+    /// Services document pages have links for sharing to, in order.
+    share: Vec<Service>,
+
+    /// Whether to write a page mapping how documents link to one another.
+    graph: bool,
+
+    /// How images referenced by documents are optimized, if they are.
+    images: Option<Images>,
+
+    /// Whether to give stylesheets and scripts a hash of their content in
+    /// their names.
+    fingerprint: bool,
+
+    /// Whether to give footnotes as sidenotes, unless documents say otherwise.
+    sidenotes: bool,
+}
+
+impl Site {
+    /// Gets the title of the site, falling back to the name of the current
+    /// directory if no title has been set.
+    #[must_use]
+    pub fn title(&self) -> String {
+        match &self.title {
+            Some(title) => title.to_string(),
+            None => env::current_dir()
+                .ok()
+                .and_then(output_name)
+                .unwrap_or_else(|| "HOME".to_owned()),
+        }
+    }
+
+    /// Sets the title of the site.
+    #[inline]
+    pub fn set_title(&mut self, title: impl AsRef<str>) {
+        self.title = Some(title.as_ref().into());
+    }
+
+    /// Gets the label of links back to the index, "HOME" by default.
+    #[inline]
+    #[must_use]
+    pub fn home_label(&self) -> &str {
+        self.home_label.as_deref().unwrap_or("HOME")
+    }
+
+    /// Gets the URL the site will be hosted at, if one has been set.
+    #[inline]
+    #[must_use]
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    /// Gets the author of documents which do not name their own, if set.
+    #[inline]
+    #[must_use]
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Creates a link to the given href from the page at `from`, both being
+    /// relative to the root of the output directory, as configured by the
+    /// [`LinkMode`]. The href is percent-encoded.
+    ///
+    /// [`LinkMode`]: LinkMode
+    #[must_use]
+    pub fn href(&self, from: &str, href: &str) -> String {
+        match self.link_mode {
+            // These hrefs are of files, which may have a '#' or '?' in their
+            // names, so they are compared as paths rather than parsed.
+            LinkMode::Relative => href::encode(&Path::new(href).relative_to(Path::new(from))),
+            LinkMode::Absolute => {
+                href::join_base(self.base_url().unwrap_or_default(), &href::encode(href))
+            }
+        }
+    }
+
+    /// Gets the full URL of the given href, relative to the root of the output
+    /// directory, if the site's base URL has been set.
+    #[inline]
+    #[must_use]
+    pub fn url(&self, href: &str) -> Option<String> {
+        Some(href::join_base(self.base_url()?, &href::encode(href)))
+    }
+
+    /// Returns true if the hrefs of documents should be slugified.
+    #[inline]
+    #[must_use]
+    pub fn slug_hrefs(&self) -> bool {
+        self.slug_hrefs
+    }
+
+    /// Gets the language the site is written in, "en" by default.
+    #[inline]
+    #[must_use]
+    pub fn lang(&self) -> &str {
+        self.lang.as_deref().unwrap_or("en")
+    }
+
+    /// Gets the languages the site is translated into, see [`lang`].
+    ///
+    /// [`lang`]: crate::lang
+    #[inline]
+    #[must_use]
+    pub fn languages(&self) -> &[Rc<str>] {
+        &self.languages
+    }
+
+    /// Gets the path to the site's favicon, if one has been set.
+    #[inline]
+    #[must_use]
+    pub fn favicon(&self) -> Option<&str> {
+        self.favicon.as_deref()
+    }
+
+    /// Gets the path to the site's apple-touch-icon, if one has been set.
+    #[inline]
+    #[must_use]
+    pub fn touch_icon(&self) -> Option<&str> {
+        self.touch_icon.as_deref()
+    }
+
+    /// Returns true if the full body of documents should be searchable.
+    #[inline]
+    #[must_use]
+    pub fn search_body(&self) -> bool {
+        self.search_body
+    }
+
+    /// Gets which date documents are grouped by on the archive page.
+    #[inline]
+    #[must_use]
+    pub fn archive_by(&self) -> ArchiveDate {
+        self.archive_by
+    }
+
+    /// Returns true if pages should have a light/dark color scheme toggle.
+    #[inline]
+    #[must_use]
+    pub fn theme_toggle(&self) -> bool {
+        self.theme_toggle
+    }
+
+    /// Gets the comment service embedded in document pages, if any.
+    #[inline]
+    #[must_use]
+    pub fn comments(&self) -> Option<&Comments> {
+        self.comments.as_ref()
+    }
+
+    /// Gets the number of related documents listed on document pages, zero if
+    /// they should not be listed.
+    #[inline]
+    #[must_use]
+    pub fn related(&self) -> usize {
+        self.related.unwrap_or(related::DEFAULT_COUNT)
+    }
+
+    /// Sets the number of related documents listed on document pages.
+    #[inline]
+    pub fn set_related(&mut self, related: usize) {
+        self.related = Some(related);
+    }
+
+    /// Gets the number of recently updated documents listed at the top of
+    /// indexes, zero if they should not be listed.
+    #[inline]
+    #[must_use]
+    pub fn recent(&self) -> usize {
+        self.recent.unwrap_or(DEFAULT_RECENT)
+    }
+
+    /// Sets the number of recently updated documents listed on indexes.
+    #[inline]
+    pub fn set_recent(&mut self, recent: usize) {
+        self.recent = Some(recent);
+    }
+
+    /// Gets the services document pages have links for sharing to, none if
+    /// pages should not have share links.
+    #[inline]
+    #[must_use]
+    pub fn share(&self) -> &[Service] {
+        &self.share
+    }
+
+    /// Returns true if a page mapping how documents link to one another should
+    /// be written.
+    #[inline]
+    #[must_use]
+    pub fn graph(&self) -> bool {
+        self.graph
+    }
+
+    /// Gets how images referenced by documents are optimized, [`None`] if they
+    /// are not copied into the output at all.
+    ///
+    /// [`None`]: None
+    #[inline]
+    #[must_use]
+    pub fn images(&self) -> Option<&Images> {
+        self.images.as_ref()
+    }
+
+    /// Returns true if stylesheets and scripts should be fingerprinted, see
+    /// the [`fingerprint`] module.
+    ///
+    /// [`fingerprint`]: crate::fingerprint
+    #[inline]
+    #[must_use]
+    pub fn fingerprint(&self) -> bool {
+        self.fingerprint
+    }
+
+    /// Returns true if the footnotes of documents should be given as
+    /// sidenotes, for documents not saying otherwise in their front matter.
+    #[inline]
+    #[must_use]
+    pub fn sidenotes(&self) -> bool {
+        self.sidenotes
+    }
+
+    /// Returns a [`Vec`] of tuples in which the first item is the URL of an
+    /// endpoint receiving mentions of the site's pages and the second is the
+    /// `rel` attribute it should be linked with.
+    ///
+    /// [`Vec`]: Vec
+    #[must_use]
+    pub fn mention_endpoints(&self) -> Vec<(&str, &'static str)> {
+        [
+            (&self.webmention, "webmention"),
+            (&self.pingback, "pingback"),
+        ]
+        .into_iter()
+        .filter_map(|(url, rel)| Some((url.as_deref()?, rel)))
+        .collect()
+    }
+
+    /// Formats the given date as configured for display on generated pages.
+    /// The default format is used if the configured one is invalid.
+    #[must_use]
+    pub fn format_date(&self, date: time::OffsetDateTime) -> String {
+        let format = self.date_format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT);
+        let items = format_description::parse_borrowed::<2>(format)
+            .or_else(|_| format_description::parse_borrowed::<2>(DEFAULT_DATE_FORMAT))
+            .unwrap_or_default();
+
+        date.format(&items).unwrap_or_default()
+    }
+
+    /// Creates an HTML `<time>` element for the given date, its text being the
+    /// date as formatted by [`format_date`].
+    ///
+    /// [`format_date`]: Site::format_date
+    #[must_use]
+    pub fn date_html(&self, date: time::OffsetDateTime) -> String {
+        format!(
+            "<time datetime=\"{}\">{}</time>",
+            date.format(&Rfc3339).unwrap_or_default(),
+            self.format_date(date)
+        )
+    }
+
+    /// Returns a [`Vec`] of tuples in which the first item is the href of an
+    /// icon in the output directory and the second is the `rel` attribute it
+    /// should be linked with.
+    ///
+    /// [`Vec`]: Vec
+    #[must_use]
+    pub fn icons(&self) -> Vec<(String, &'static str)> {
+        [
+            (self.favicon(), "icon"),
+            (self.touch_icon(), "apple-touch-icon"),
+        ]
+        .into_iter()
+        .filter_map(|(path, rel)| Some((output_name(path?)?, rel)))
+        .collect()
+    }
+}
+
+/// Gets the name an asset at the given path will be given when copied into
+/// the root of the output directory.
+#[inline]
+#[must_use]
+pub fn output_name(path: impl AsRef<Path>) -> Option<String> {
+    Some(path.as_ref().file_name()?.to_str()?.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_date_test() {
+        let date = time::OffsetDateTime::from_unix_timestamp(0).unwrap();
+        let mut site = Site::default();
+
+        assert_eq!(site.format_date(date), "1 January 1970, 12:00 AM");
+
+        site.date_format = Some("[year]-[month]-[day]".into());
+        assert_eq!(site.format_date(date), "1970-01-01");
+
+        site.date_format = Some("[not a component]".into());
+        assert_eq!(site.format_date(date), "1 January 1970, 12:00 AM");
+    }
+
+    #[test]
+    fn href_test() {
+        let mut site = Site::default();
+
+        assert_eq!(site.href("blog/a.html", "index.html"), "../index.html");
+        assert_eq!(site.href("index.html", "a b.html"), "a%20b.html");
+        assert_eq!(site.href("en/a.html", "C#.html"), "../C%23.html");
+
+        site.link_mode = LinkMode::Absolute;
+        assert_eq!(site.href("blog/a.html", "index.html"), "/index.html");
+
+        site.base_url = Some("https://example.com/notes/".into());
+        assert_eq!(
+            site.href("blog/a.html", "blog/a.html"),
+            "https://example.com/notes/blog/a.html"
+        );
+        assert_eq!(
+            site.url("a b.html").as_deref(),
+            Some("https://example.com/notes/a%20b.html")
+        );
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Styling of text written to the terminal. Styles are only applied when
+//! standard output is a terminal and the `NO_COLOR` environment variable is
+//! unset or empty (see https://no-color.org).
+
+use std::{
+    env,
+    fmt::Display,
+    io::{self, IsTerminal},
+    sync::OnceLock,
+};
+
+/// A style that may be applied to text written to the terminal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Style {
+    /// Bold text, for summaries.
+    Bold,
+
+    /// Bold cyan text, for prompts awaiting input.
+    Prompt,
+
+    /// Green text, for things that succeeded.
+    Success,
+
+    /// Red text, for things that failed.
+    Failure,
+
+    /// Yellow text, for things that may be a problem.
+    Warning,
+}
+
+impl Style {
+    /// Gets the ANSI escape code which begins this [`Style`].
+    ///
+    /// [`Style`]: Style
+    #[must_use]
+    const fn code(self) -> &'static str {
+        match self {
+            Style::Bold => "\x1b[1m",
+            Style::Prompt => "\x1b[1;36m",
+            Style::Success => "\x1b[32m",
+            Style::Failure => "\x1b[31m",
+            Style::Warning => "\x1b[33m",
+        }
+    }
+
+    /// Applies this [`Style`] to the given text if styling is enabled,
+    /// otherwise returns the text as is.
+    ///
+    /// [`Style`]: Style
+    #[must_use]
+    pub fn paint(self, text: impl Display) -> String {
+        match enabled() {
+            true => format!("{}{}\x1b[0m", self.code(), text),
+            false => text.to_string(),
+        }
+    }
+}
+
+/// Returns true if output should be styled, checked once and remembered.
+#[must_use]
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+
+    *ENABLED.get_or_init(|| {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        !no_color && io::stdout().is_terminal()
+    })
+}
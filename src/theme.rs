@@ -0,0 +1,110 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! The default theme written alongside generated pages. Its assets are built
+//! into whim, so that a site may be built without any other files, but any of
+//! them may be replaced by keeping a file of the same name in the library's
+//! theme directory, `theme` unless configured otherwise. Every other file in
+//! that directory is copied into the output as well, apart from layouts.
+
+use crate::{href, layout};
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the library, holding files which override the
+/// default theme when none is configured.
+pub const OVERRIDES_DIR: &str = "theme";
+
+/// The href of the stylesheet, relative to the root of the output directory.
+pub const STYLESHEET_HREF: &str = "styles.css";
+
+/// The default stylesheet, supporting both light and dark color schemes.
+pub const STYLESHEET: &str = include_str!("../theme/styles.css");
+
+/// Button toggling between light and dark color schemes.
+pub const TOGGLE_BUTTON: &str =
+    r#"<button id="theme-toggle" type="button" aria-label="Toggle dark mode">◐</button>"#;
+
+/// The href of the [`TOGGLE_SCRIPT`], relative to the root of the output
+/// directory.
+///
+/// [`TOGGLE_SCRIPT`]: TOGGLE_SCRIPT
+pub const TOGGLE_SCRIPT_HREF: &str = "theme.js";
+
+/// Script applying a previously chosen color scheme and handling clicks of the
+/// [`TOGGLE_BUTTON`], the choice is remembered in local storage.
+///
+/// [`TOGGLE_BUTTON`]: TOGGLE_BUTTON
+pub const TOGGLE_SCRIPT: &str = include_str!("../theme/theme.js");
+
+/// Lists every file in the given theme directory as tuples of the href it
+/// overrides, relative to the root of the output directory, and its path.
+/// Layouts are not listed, and a missing directory has no files.
+///
+/// # Errors
+///
+/// Returns an error if the directory's path cannot be used in a glob pattern.
+pub fn overrides(dir: impl AsRef<Path>) -> Result<Vec<(String, PathBuf)>, glob::PatternError> {
+    let dir = dir.as_ref();
+    let pattern = format!("{}/**/*", glob::Pattern::escape(&dir.to_string_lossy()));
+
+    Ok(glob::glob(&pattern)?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let href = href::from_path(path.strip_prefix(dir).ok()?);
+
+            match href.starts_with(&format!("{}/", layout::TEMPLATES_DIR)) {
+                true => None,
+                false => Some((href, path)),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::LibraryHtml;
+    use std::fs;
+
+    #[test]
+    fn overrides_test() {
+        let dir = std::env::temp_dir().join("whim-theme-test");
+        fs::create_dir_all(dir.join("fonts")).unwrap();
+        fs::write(dir.join("styles.css"), "body {}").unwrap();
+        fs::write(dir.join("fonts/serif.woff2"), "").unwrap();
+        fs::create_dir_all(dir.join(layout::TEMPLATES_DIR)).unwrap();
+        fs::write(dir.join("templates/default.html"), "").unwrap();
+
+        let mut files = overrides(&dir).unwrap();
+        files.sort();
+
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "fonts/serif.woff2".to_owned(),
+                    dir.join("fonts/serif.woff2")
+                ),
+                ("styles.css".to_owned(), dir.join("styles.css")),
+            ]
+        );
+        assert!(overrides(dir.join("missing")).unwrap().is_empty());
+
+        let out = dir.join("out");
+        LibraryHtml::new(Vec::new())
+            .with_output(STYLESHEET_HREF, STYLESHEET)
+            .with_overrides(files)
+            .write(&out)
+            .unwrap();
+
+        assert!(out.join("fonts/serif.woff2").is_file());
+        assert_eq!(
+            fs::read_to_string(out.join("styles.css")).unwrap(),
+            "body {}"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
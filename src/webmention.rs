@@ -0,0 +1,364 @@
+// Copyright (c) Evan Overman 2023 (https://an-prata.it).
+// Licensed under the MIT License.
+// See LICENSE file in repository root for full text.
+
+//! Outgoing [webmentions](https://www.w3.org/TR/webmention/). When enabled,
+//! each build queues a mention of every external link in the documents which
+//! have changed, kept in `.whim-mentions.ron` until `whim webmention send`
+//! sends them once the site has been published. Sent mentions are kept in
+//! `.whim-mentions-sent.ron` so that they are not queued again until their
+//! document changes. Requests are made with `curl`.
+
+use crate::hash::Digest;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, error, fmt, fs, io, path::Path, process, result};
+
+/// Path of the queue of mentions to send, relative to the library's directory.
+pub const MENTIONS_FILE: &str = ".whim-mentions.ron";
+
+/// Path of the mentions already sent, relative to the library's directory.
+pub const SENT_FILE: &str = ".whim-mentions-sent.ron";
+
+/// Seconds to wait for a response before giving up on a request.
+const TIMEOUT_SECS: &str = "15";
+
+/// Format given to `curl --write-out` to end its output with the URL fetched
+/// last, after any redirects, on a line of its own.
+const EFFECTIVE_URL: &str = "\n%{url_effective}";
+
+/// A mention of the `target` URL by the page at the `source` URL.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Mention {
+    pub source: String,
+    pub target: String,
+
+    /// Hash of the source document when the mention was made, so that the
+    /// same link is mentioned again once the document changes.
+    #[serde(default)]
+    pub hash: Digest,
+}
+
+/// Reads the set of mentions at the given path, a missing file being an empty
+/// set.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read or parsed.
+pub fn queued(path: impl AsRef<Path>) -> Result<BTreeSet<Mention>> {
+    match fs::read_to_string(path) {
+        Ok(s) => ron::from_str(&s).map_err(|_| Error::DeserializationError),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(_) => Err(Error::FileReadError),
+    }
+}
+
+/// Writes the given set of mentions to the given path, removing the file if
+/// the set is empty.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written or removed.
+pub fn save(path: impl AsRef<Path>, mentions: &BTreeSet<Mention>) -> Result<()> {
+    let path = path.as_ref();
+
+    match mentions.is_empty() {
+        true if path.exists() => fs::remove_file(path).map_err(|_| Error::FileWriteError),
+        true => Ok(()),
+        false => fs::write(
+            path,
+            ron::ser::to_string_pretty(mentions, ron::ser::PrettyConfig::default())
+                .map_err(|_| Error::SerializationError)?,
+        )
+        .map_err(|_| Error::FileWriteError),
+    }
+}
+
+/// Sends the given [`Mention`] to the webmention endpoint of its target.
+///
+/// # Errors
+///
+/// Returns an error if `curl` could not be run, the target has no endpoint,
+/// or the endpoint did not accept the mention.
+///
+/// [`Mention`]: Mention
+pub fn send(mention: &Mention) -> Result<()> {
+    let output = curl(&[
+        "--location",
+        "--dump-header",
+        "-",
+        "--write-out",
+        EFFECTIVE_URL,
+        &mention.target,
+    ])?;
+    let (page, url) = split_effective_url(&output, &mention.target);
+    let endpoint = discover(page, url).ok_or(Error::NoEndpoint)?;
+
+    log::debug!("sending mention of '{}' to '{}'", mention.target, endpoint);
+
+    let null = match cfg!(windows) {
+        true => "NUL",
+        false => "/dev/null",
+    };
+
+    let status = curl(&[
+        "--output",
+        null,
+        "--write-out",
+        "%{http_code}",
+        "--data-urlencode",
+        &format!("source={}", mention.source),
+        "--data-urlencode",
+        &format!("target={}", mention.target),
+        &endpoint,
+    ])?;
+
+    match status.trim().starts_with('2') {
+        true => Ok(()),
+        false => {
+            log::error!("'{}' responded with status {}", endpoint, status.trim());
+            Err(Error::RequestFailed)
+        }
+    }
+}
+
+/// Runs `curl` quietly with the given arguments, returning its output.
+fn curl(args: &[&str]) -> Result<String> {
+    let output = process::Command::new("curl")
+        .args(["--silent", "--show-error", "--max-time", TIMEOUT_SECS])
+        .args(args)
+        .output()
+        .map_err(|_| Error::SpawnError)?;
+
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => {
+            log::error!("{}", String::from_utf8_lossy(&output.stderr).trim());
+            Err(Error::RequestFailed)
+        }
+    }
+}
+
+/// Splits output of `curl` written with [`EFFECTIVE_URL`] into the response
+/// and the URL it came from, which is the given target if there is none.
+///
+/// [`EFFECTIVE_URL`]: EFFECTIVE_URL
+fn split_effective_url<'a>(output: &'a str, target: &'a str) -> (&'a str, &'a str) {
+    match output.rsplit_once('\n') {
+        Some((response, url)) if !url.trim().is_empty() => (response, url.trim()),
+        _ => (output, target),
+    }
+}
+
+/// Finds the webmention endpoint in the given response, headers followed by
+/// the page, fetched from the given URL. `Link` headers take precedence over
+/// `<link>` and `<a>` elements, the endpoint is resolved against the URL, so
+/// it should be the one fetched last if any redirects were followed.
+#[must_use]
+pub fn discover(response: &str, url: &str) -> Option<String> {
+    // Each redirect followed adds a block of headers, the last is the page's.
+    let mut headers = "";
+    let mut body = response;
+
+    while body.starts_with("HTTP/") {
+        (headers, body) = body
+            .split_once("\r\n\r\n")
+            .or_else(|| body.split_once("\n\n"))
+            .unwrap_or((body, ""));
+    }
+
+    let header = headers
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("link").then_some(value)
+        })
+        .flat_map(|value| value.split(','))
+        .find(|link| {
+            link.split(';').skip(1).any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("rel=")
+                    .is_some_and(|rel| has_webmention(rel.trim_matches('"')))
+            })
+        })
+        .and_then(|link| {
+            let link = link.trim();
+            Some(link.strip_prefix('<')?.split_once('>')?.0.to_owned())
+        });
+
+    let element = || {
+        body.split('<').skip(1).find_map(|tag| {
+            let tag = tag.split_once('>')?.0;
+            let name = tag.split_whitespace().next()?.to_lowercase();
+
+            if (name != "link" && name != "a") || !attribute(tag, "rel").is_some_and(has_webmention)
+            {
+                return None;
+            }
+
+            attribute(tag, "href").map(str::to_owned)
+        })
+    };
+
+    header
+        .or_else(element)
+        .map(|endpoint| resolve(url, &endpoint))
+}
+
+/// Returns true if the given space separated list of rels has "webmention".
+fn has_webmention(rels: &str) -> bool {
+    rels.split_whitespace()
+        .any(|rel| rel.eq_ignore_ascii_case("webmention"))
+}
+
+/// Gets the value of the quoted attribute of the given name in an HTML tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    tag.match_indices(name).find_map(|(i, _)| {
+        let preceded = tag[..i].ends_with(char::is_whitespace);
+        let rest = tag[i + name.len()..]
+            .trim_start()
+            .strip_prefix('=')?
+            .trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = rest[1..].split(quote).next()?;
+        preceded.then_some(value)
+    })
+}
+
+/// Resolves the given href against the given absolute URL.
+#[must_use]
+pub fn resolve(base: &str, href: &str) -> String {
+    if href.contains("://") {
+        return href.to_owned();
+    }
+
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+    match href {
+        "" => base.to_owned(),
+        h if h.starts_with("//") => format!("{}:{}", scheme, h),
+        h if h.starts_with('/') => format!("{}://{}{}", scheme, host, h),
+        h => {
+            let path = rest.split(['?', '#']).next().unwrap_or_default();
+            let dir = path.rfind('/').map_or(path, |i| &path[..=i]);
+            match dir.contains('/') {
+                true => format!("{}://{}{}", scheme, dir, h),
+                false => format!("{}://{}/{}", scheme, dir, h),
+            }
+        }
+    }
+}
+
+/// The result type of webmention related functions.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error that may occur while queueing or sending mentions.
+#[derive(Debug)]
+pub enum Error {
+    /// I/O failure to read the queue.
+    FileReadError,
+
+    /// I/O failure to write the queue.
+    FileWriteError,
+
+    /// The set of mentions is not valid RON.
+    DeserializationError,
+
+    /// The set of mentions could not be serialized.
+    SerializationError,
+
+    /// `curl` could not be started, it may not be installed.
+    SpawnError,
+
+    /// The target does not advertise a webmention endpoint.
+    NoEndpoint,
+
+    /// A request failed or was refused.
+    RequestFailed,
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discover_test() {
+        let target = "https://example.com/posts/a.html";
+        let response = "HTTP/1.1 301 Moved\r\n\
+            Location: /posts/a.html\r\n\
+            Link: </wrong>; rel=\"webmention\"\r\n\
+            \r\n\
+            HTTP/1.1 200 OK\r\n\
+            Link: <https://example.com/>; rel=\"home\", </mention>; rel=\"webmention\"\r\n\
+            \r\n\
+            <link rel=\"webmention\" href=\"/ignored\">";
+
+        assert_eq!(
+            discover(response, target).as_deref(),
+            Some("https://example.com/mention")
+        );
+        assert_eq!(
+            discover(
+                "HTTP/1.1 200 OK\r\n\r\n<a href='endpoint' rel='nofollow webmention'>",
+                target
+            )
+            .as_deref(),
+            Some("https://example.com/posts/endpoint")
+        );
+        assert_eq!(
+            discover("HTTP/1.1 200 OK\r\n\r\n<a href=\"/x\">", target),
+            None
+        );
+
+        let output = format!("{}\nhttps://w.io/new/a.html", response);
+        let (response, url) = split_effective_url(&output, target);
+        assert_eq!(url, "https://w.io/new/a.html");
+        assert_eq!(
+            discover(response, url).as_deref(),
+            Some("https://w.io/mention")
+        );
+        assert_eq!(
+            discover("HTTP/1.1 200 OK\r\n\r\n<a rel='webmention' href='m'>", url).as_deref(),
+            Some("https://w.io/new/m")
+        );
+        assert_eq!(split_effective_url("page", target), ("page", target));
+    }
+
+    #[test]
+    fn queue_test() {
+        let path = std::env::temp_dir().join("whim-mentions-test.ron");
+        let mention = Mention {
+            source: "https://example.com/a.html".into(),
+            target: "https://w.io/".into(),
+            hash: Digest(1),
+        };
+
+        save(&path, &BTreeSet::from([mention.clone()])).unwrap();
+        assert_eq!(queued(&path).unwrap(), BTreeSet::from([mention]));
+
+        save(&path, &BTreeSet::new()).unwrap();
+        assert!(!path.exists());
+        assert!(queued(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn resolve_test() {
+        let base = "https://example.com/a/b.html?q";
+
+        assert_eq!(resolve(base, "https://w.io/m"), "https://w.io/m");
+        assert_eq!(resolve(base, "//w.io/m"), "https://w.io/m");
+        assert_eq!(resolve(base, "/m"), "https://example.com/m");
+        assert_eq!(resolve(base, "m"), "https://example.com/a/m");
+        assert_eq!(resolve("https://example.com", "m"), "https://example.com/m");
+        assert_eq!(resolve(base, ""), base);
+    }
+}
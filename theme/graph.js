@@ -0,0 +1,64 @@
+const svg = document.getElementById("graph");
+const graph = JSON.parse(document.getElementById("graph-data").textContent);
+const ns = "http://www.w3.org/2000/svg";
+const size = 1000;
+
+// Nodes start on a circle, then repel one another while edges pull linked
+// nodes together, for a fixed number of steps.
+const nodes = graph.nodes.map((node, i) => {
+    const angle = (2 * Math.PI * i) / graph.nodes.length;
+    return { ...node, x: Math.cos(angle) * size / 3, y: Math.sin(angle) * size / 3 };
+});
+
+for (let step = 0; step < 300; step++) {
+    const cooling = 1 - step / 300;
+
+    for (const a of nodes) {
+        for (const b of nodes) {
+            const dx = a.x - b.x, dy = a.y - b.y;
+            const distance = Math.max(Math.hypot(dx, dy), 1);
+            a.x += (dx / distance) * (2000 / (distance * distance)) * cooling;
+            a.y += (dy / distance) * (2000 / (distance * distance)) * cooling;
+        }
+
+        a.x -= a.x * 0.01 * cooling;
+        a.y -= a.y * 0.01 * cooling;
+    }
+
+    for (const [from, to] of graph.edges) {
+        const a = nodes[from], b = nodes[to];
+        const dx = (b.x - a.x) * 0.02 * cooling, dy = (b.y - a.y) * 0.02 * cooling;
+        a.x += dx;
+        a.y += dy;
+        b.x -= dx;
+        b.y -= dy;
+    }
+}
+
+const element = (name, attributes) => {
+    const e = document.createElementNS(ns, name);
+    Object.entries(attributes).forEach(([key, value]) => e.setAttribute(key, value));
+    return e;
+};
+
+const xs = nodes.map((n) => n.x), ys = nodes.map((n) => n.y);
+const margin = 80;
+svg.setAttribute("viewBox", [
+    Math.min(...xs) - margin,
+    Math.min(...ys) - margin,
+    Math.max(...xs) - Math.min(...xs) + 2 * margin,
+    Math.max(...ys) - Math.min(...ys) + 2 * margin,
+].join(" "));
+
+for (const [from, to] of graph.edges) {
+    const a = nodes[from], b = nodes[to];
+    svg.append(element("line", { x1: a.x, y1: a.y, x2: b.x, y2: b.y }));
+}
+
+for (const node of nodes) {
+    const link = element("a", { href: node.href });
+    const label = element("text", { x: node.x, y: node.y - 10, "text-anchor": "middle" });
+    label.textContent = node.title;
+    link.append(element("circle", { cx: node.x, cy: node.y, r: 5 }), label);
+    svg.append(link);
+}
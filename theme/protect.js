@@ -0,0 +1,39 @@
+const form = document.getElementById("protected");
+const passphrase = document.getElementById("passphrase");
+const error = document.getElementById("protected-error");
+const locked = JSON.parse(document.getElementById("protected-data").textContent);
+const bytes = (base64) => Uint8Array.from(atob(base64), (c) => c.charCodeAt(0));
+
+form.addEventListener("submit", async (event) => {
+    event.preventDefault();
+
+    const material = await crypto.subtle.importKey(
+        "raw",
+        new TextEncoder().encode(passphrase.value),
+        "PBKDF2",
+        false,
+        ["deriveKey"]
+    );
+
+    const key = await crypto.subtle.deriveKey(
+        { name: "PBKDF2", hash: "SHA-256", salt: bytes(locked.salt), iterations: locked.iterations },
+        material,
+        { name: "AES-GCM", length: 256 },
+        false,
+        ["decrypt"]
+    );
+
+    try {
+        const content = await crypto.subtle.decrypt(
+            { name: "AES-GCM", iv: bytes(locked.nonce) },
+            key,
+            bytes(locked.ciphertext)
+        );
+
+        const unlocked = document.createElement("div");
+        unlocked.innerHTML = new TextDecoder().decode(content);
+        form.replaceWith(...unlocked.childNodes);
+    } catch {
+        error.hidden = false;
+    }
+});
@@ -0,0 +1,29 @@
+const input = document.getElementById("search");
+const results = document.getElementById("results");
+
+fetch("search-index.json")
+    .then((response) => response.json())
+    .then((index) => {
+        input.addEventListener("input", () => {
+            const terms = input.value.toLowerCase().split(/\s+/).filter((t) => t);
+            const matches = (entry, term) =>
+                entry.title.toLowerCase().includes(term) ||
+                entry.excerpt.toLowerCase().includes(term) ||
+                (entry.body || []).some((word) => word.startsWith(term));
+
+            results.replaceChildren(
+                ...index
+                    .filter((entry) => terms.length && terms.every((t) => matches(entry, t)))
+                    .map((entry) => {
+                        const item = document.createElement("li");
+                        const link = document.createElement("a");
+                        const excerpt = document.createElement("p");
+                        link.href = entry.href;
+                        link.textContent = entry.title;
+                        excerpt.textContent = entry.excerpt;
+                        item.append(link, excerpt);
+                        return item;
+                    })
+            );
+        });
+    });
@@ -0,0 +1,17 @@
+const root = document.documentElement;
+const saved = localStorage.getItem("theme");
+
+if (saved) {
+    root.dataset.theme = saved;
+}
+
+document.addEventListener("DOMContentLoaded", () => {
+    document.getElementById("theme-toggle").addEventListener("click", () => {
+        const dark = root.dataset.theme
+            ? root.dataset.theme === "dark"
+            : matchMedia("(prefers-color-scheme: dark)").matches;
+
+        root.dataset.theme = dark ? "light" : "dark";
+        localStorage.setItem("theme", root.dataset.theme);
+    });
+});